@@ -0,0 +1,253 @@
+//! Synchronous wrapper around the asynchronous [`Client`](crate::Client).
+//!
+//! This is intended for command line tools and scripts which don't otherwise use async code. The
+//! [`Client`] in this module owns a small single-threaded Tokio runtime, and blocks the calling
+//! thread until each command completes.
+//!
+//! Only available with the `blocking` feature.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use mpd_client::{blocking::Client, commands};
+//!
+//! # fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! let client = Client::connect_tcp("localhost:6600")?;
+//! let status = client.command(commands::Status)?;
+//! println!("{status:?}");
+//! # Ok(())
+//! # }
+//! ```
+
+use tokio::{
+    io::{AsyncRead, AsyncWrite},
+    net::ToSocketAddrs,
+    runtime::{self, Runtime},
+};
+
+#[cfg(unix)]
+use std::path::Path;
+use std::time::Duration;
+
+use crate::{
+    commands::{Command, CommandList},
+    errors::CommandError,
+    raw::{Frame, MpdProtocolError, RawCommand, RawCommandList},
+    ConnectWithPasswordError,
+};
+
+/// Blocking MPD client.
+///
+/// Unlike the [asynchronous client](crate::Client), the background task driving the connection
+/// only runs while a method of this client is executing. State changes are therefore not
+/// available, and features relying on the connection being driven while idle (such as
+/// [keepalive](crate::Client::set_keepalive)) are not supported.
+///
+/// # Panics
+///
+/// All methods of this type will panic when called from within an asynchronous runtime.
+#[derive(Debug)]
+pub struct Client {
+    runtime: Runtime,
+    inner: crate::Client,
+}
+
+impl Client {
+    /// Connect to the MPD server using the given connection.
+    ///
+    /// See [`crate::Client::connect`].
+    ///
+    /// # Errors
+    ///
+    /// This will return an error if creating the runtime or sending the initial commands over the
+    /// given transport fails.
+    pub fn connect<C>(connection: C) -> Result<Self, MpdProtocolError>
+    where
+        C: AsyncRead + AsyncWrite + Unpin + Send + 'static,
+    {
+        let runtime = new_runtime()?;
+        let (inner, _) = runtime.block_on(crate::Client::connect(connection))?;
+        Ok(Client { runtime, inner })
+    }
+
+    /// Connect to the MPD server listening on the given TCP address.
+    ///
+    /// See [`crate::Client::connect_tcp`].
+    ///
+    /// # Errors
+    ///
+    /// This will return an error if creating the runtime, establishing the TCP connection or
+    /// sending the initial commands fails.
+    pub fn connect_tcp<A>(address: A) -> Result<Self, MpdProtocolError>
+    where
+        A: ToSocketAddrs,
+    {
+        let runtime = new_runtime()?;
+        let (inner, _) = runtime.block_on(crate::Client::connect_tcp(address))?;
+        Ok(Client { runtime, inner })
+    }
+
+    /// Connect to the MPD server listening on the Unix socket at the given path.
+    ///
+    /// See [`crate::Client::connect_unix`].
+    ///
+    /// # Errors
+    ///
+    /// This will return an error if creating the runtime, connecting to the socket or sending the
+    /// initial commands fails.
+    #[cfg(unix)]
+    pub fn connect_unix<P>(path: P) -> Result<Self, MpdProtocolError>
+    where
+        P: AsRef<Path>,
+    {
+        let runtime = new_runtime()?;
+        let (inner, _) = runtime.block_on(crate::Client::connect_unix(path))?;
+        Ok(Client { runtime, inner })
+    }
+
+    /// Connect to the MPD server specified by the environment, in the same way as `mpc`.
+    ///
+    /// See [`crate::Client::connect_from_env`].
+    ///
+    /// # Errors
+    ///
+    /// This returns errors in the same conditions as [`crate::Client::connect_from_env`], and
+    /// additionally if creating the runtime fails.
+    pub fn connect_from_env() -> Result<Self, ConnectWithPasswordError> {
+        let runtime = new_runtime()?;
+        let (inner, _) = runtime.block_on(crate::Client::connect_from_env())?;
+        Ok(Client { runtime, inner })
+    }
+
+    /// Connect to the MPD server using the given connection, optionally sending a password.
+    ///
+    /// See [`crate::Client::connect_with_password_opt`].
+    ///
+    /// # Errors
+    ///
+    /// This returns errors in the same conditions as [`crate::Client::connect_with_password_opt`],
+    /// and additionally if creating the runtime fails.
+    pub fn connect_with_password_opt<C>(
+        connection: C,
+        password: Option<&str>,
+    ) -> Result<Self, ConnectWithPasswordError>
+    where
+        C: AsyncRead + AsyncWrite + Unpin + Send + 'static,
+    {
+        let runtime = new_runtime()?;
+        let (inner, _) = runtime.block_on(crate::Client::connect_with_password_opt(
+            connection, password,
+        ))?;
+        Ok(Client { runtime, inner })
+    }
+
+    /// Send a [command](crate::commands), blocking until the response is received.
+    ///
+    /// # Errors
+    ///
+    /// See [`crate::Client::command`].
+    pub fn command<C>(&self, cmd: C) -> Result<C::Response, CommandError>
+    where
+        C: Command,
+    {
+        self.runtime.block_on(self.inner.command(cmd))
+    }
+
+    /// Send a [command](crate::commands), failing with [`CommandError::Timeout`] if the server
+    /// doesn't respond within the given duration.
+    ///
+    /// # Errors
+    ///
+    /// See [`crate::Client::command_with_timeout`].
+    pub fn command_with_timeout<C>(
+        &self,
+        cmd: C,
+        timeout: Duration,
+    ) -> Result<C::Response, CommandError>
+    where
+        C: Command,
+    {
+        self.runtime
+            .block_on(self.inner.command_with_timeout(cmd, timeout))
+    }
+
+    /// Send the given command list, blocking until the responses are received.
+    ///
+    /// # Errors
+    ///
+    /// See [`crate::Client::command_list`].
+    pub fn command_list<L>(&self, list: L) -> Result<L::Response, CommandError>
+    where
+        L: CommandList,
+    {
+        self.runtime.block_on(self.inner.command_list(list))
+    }
+
+    /// Send the given raw command, blocking until the response is received.
+    ///
+    /// # Errors
+    ///
+    /// See [`crate::Client::raw_command`].
+    pub fn raw_command(&self, command: RawCommand) -> Result<Frame, CommandError> {
+        self.runtime.block_on(self.inner.raw_command(command))
+    }
+
+    /// Send the given raw command list, blocking until the responses are received.
+    ///
+    /// # Errors
+    ///
+    /// See [`crate::Client::raw_command_list`].
+    pub fn raw_command_list(&self, commands: RawCommandList) -> Result<Vec<Frame>, CommandError> {
+        self.runtime.block_on(self.inner.raw_command_list(commands))
+    }
+
+    /// Set the default timeout for commands sent using this client.
+    ///
+    /// See [`crate::Client::set_command_timeout`].
+    pub fn set_command_timeout(&self, timeout: Option<Duration>) {
+        self.inner.set_command_timeout(timeout);
+    }
+
+    /// Get the protocol version the underlying connection is using.
+    pub fn protocol_version(&self) -> &str {
+        self.inner.protocol_version()
+    }
+}
+
+fn new_runtime() -> Result<Runtime, MpdProtocolError> {
+    runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .map_err(MpdProtocolError::from)
+}
+
+#[cfg(test)]
+mod tests {
+    use tokio_test::io::Builder as MockBuilder;
+
+    use super::*;
+    use crate::commands::{responses::Volume, GetVolume, SetVolume};
+
+    static GREETING: &[u8] = b"OK MPD 0.23.5\n";
+
+    #[test]
+    fn commands() {
+        let io = MockBuilder::new()
+            .read(GREETING)
+            .write(b"idle\n")
+            .write(b"noidle\n")
+            .read(b"OK\n")
+            .write(b"getvol\n")
+            .read(b"volume: 42\nOK\n")
+            .write(b"setvol 10\n")
+            .read(b"OK\n")
+            .write(b"idle\n")
+            .build();
+
+        let client = Client::connect(io).unwrap();
+
+        assert_eq!(client.protocol_version(), "0.23.5");
+        assert_eq!(client.command(GetVolume).unwrap(), Volume(Some(42)));
+        client.command(SetVolume(10)).unwrap();
+    }
+}
@@ -0,0 +1,132 @@
+use bytes::Bytes;
+use futures_core::stream::Stream;
+use tracing::{trace, warn};
+
+use std::fmt;
+use std::future::Future;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use super::Client;
+use crate::commands::{self as cmds, responses as res};
+use crate::errors::CommandError;
+
+type ChunkFuture =
+    Pin<Box<dyn Future<Output = Result<Option<res::AlbumArt>, CommandError>> + Send>>;
+
+/// Stream of album art data, returned by [`Client::album_art_stream`].
+///
+/// This yields the data in the chunks sent by the server, loading the next chunk only once the
+/// previous one was consumed.
+pub struct AlbumArtStream {
+    client: Client,
+    uri: String,
+    embedded: bool,
+    size: usize,
+    mime: Option<String>,
+    received: usize,
+    first_chunk: Option<Bytes>,
+    pending: Option<ChunkFuture>,
+}
+
+impl AlbumArtStream {
+    pub(super) fn new(client: Client, uri: String, first: res::AlbumArt, embedded: bool) -> Self {
+        Self {
+            client,
+            uri,
+            embedded,
+            size: first.size,
+            mime: first.mime,
+            received: first.data.len(),
+            first_chunk: Some(first.data),
+            pending: None,
+        }
+    }
+
+    /// The total size of the album art in bytes, as reported by the server.
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    /// The MIME type of the album art, if the server provided one.
+    pub fn mime(&self) -> Option<&str> {
+        self.mime.as_deref()
+    }
+
+    fn load_chunk(&self) -> ChunkFuture {
+        let client = self.client.clone();
+        let uri = self.uri.clone();
+        let offset = self.received;
+        let embedded = self.embedded;
+
+        Box::pin(async move {
+            if embedded {
+                client
+                    .command(cmds::AlbumArtEmbedded::new(uri).offset(offset))
+                    .await
+            } else {
+                client
+                    .command(cmds::AlbumArt::new(uri).offset(offset))
+                    .await
+            }
+        })
+    }
+}
+
+impl Stream for AlbumArtStream {
+    type Item = Result<Bytes, CommandError>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        if let Some(chunk) = self.first_chunk.take() {
+            return Poll::Ready(Some(Ok(chunk)));
+        }
+
+        if self.received >= self.size {
+            return Poll::Ready(None);
+        }
+
+        let mut pending = match self.pending.take() {
+            Some(pending) => pending,
+            None => self.load_chunk(),
+        };
+
+        let result = match pending.as_mut().poll(cx) {
+            Poll::Ready(result) => result,
+            Poll::Pending => {
+                self.pending = Some(pending);
+                return Poll::Pending;
+            }
+        };
+
+        Poll::Ready(match result {
+            Ok(Some(resp)) if !resp.data.is_empty() => {
+                self.received += resp.data.len();
+                trace!(received = resp.data.len(), progress = self.received);
+                Some(Ok(resp.data))
+            }
+            Ok(_) => {
+                warn!(progress = self.received, "incomplete cover art response");
+                // Stop the stream instead of requesting the same chunk again
+                self.size = self.received;
+                None
+            }
+            Err(e) => {
+                // Errors end the stream
+                self.size = self.received;
+                Some(Err(e))
+            }
+        })
+    }
+}
+
+impl fmt::Debug for AlbumArtStream {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("AlbumArtStream")
+            .field("uri", &self.uri)
+            .field("embedded", &self.embedded)
+            .field("size", &self.size)
+            .field("mime", &self.mime)
+            .field("received", &self.received)
+            .finish_non_exhaustive()
+    }
+}
@@ -1,33 +1,76 @@
-use mpd_protocol::{AsyncConnection, Response as RawResponse};
+use mpd_protocol::{AsyncConnection, MpdProtocolError, Response as RawResponse};
 use tokio::{
     io::{AsyncRead, AsyncWrite},
-    sync::mpsc::{Receiver, UnboundedSender},
-    time::timeout,
+    sync::{
+        mpsc::{Receiver, UnboundedSender},
+        watch,
+    },
+    time::{sleep, timeout},
 };
 use tracing::{error, span, trace, warn, Instrument, Level};
 
 use std::fmt;
-use std::time::Duration;
-
-use super::CommandResponder;
+use std::io;
+use std::mem;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use super::{
+    lifecycle::{self, ConnectionEvent, DisconnectReason, EventsSender},
+    messages::MessagesSender,
+    observer::{Metered, ObserverSlot},
+    reconnect::Reconnect,
+    CommandResponder,
+};
 use crate::{
-    errors::StateChangeError,
+    commands::responses::{ChannelMessage, Response},
+    errors::{CommandError, StateChangeError},
     raw::{RawCommand, RawCommandList},
     state_changes::Subsystem,
 };
 
 type StateChangesSender = UnboundedSender<Result<Subsystem, StateChangeError>>;
 
+/// Channels connecting the run loop to the client.
+pub(super) struct Channels {
+    pub(super) commands: Receiver<(RawCommandList, CommandResponder)>,
+    pub(super) state_changes: StateChangesSender,
+    pub(super) messages: MessagesSender,
+    pub(super) keepalive: watch::Receiver<Option<Duration>>,
+    pub(super) observer: ObserverSlot,
+    pub(super) events: EventsSender,
+    pub(super) close: watch::Receiver<bool>,
+}
+
 struct State<C> {
     loop_state: LoopState,
     connection: AsyncConnection<C>,
     commands: Receiver<(RawCommandList, CommandResponder)>,
     state_changes: StateChangesSender,
+    messages: MessagesSender,
+    keepalive: watch::Receiver<Option<Duration>>,
+    observer: ObserverSlot,
+    close: watch::Receiver<bool>,
+    /// Whether the connection is reestablished after it was lost.
+    reconnect: bool,
+}
+
+impl<C> State<C> {
+    /// Get the error to report for a command whose connection was lost, since with reconnecting
+    /// enabled the client is not closed.
+    fn lost_command_error(&self, error: CommandError) -> CommandError {
+        if self.reconnect {
+            CommandError::Reconnecting
+        } else {
+            error
+        }
+    }
 }
 
 enum LoopState {
     Idling,
-    WaitingForCommandReply(CommandResponder),
+    /// Waiting for the response to a command, which was sent at the given instant.
+    WaitingForCommandReply(CommandResponder, Instant),
 }
 
 impl fmt::Debug for LoopState {
@@ -35,7 +78,7 @@ impl fmt::Debug for LoopState {
         // avoid Debug-printing the noisy internals of the contained channel type
         match self {
             LoopState::Idling => write!(f, "Idling"),
-            LoopState::WaitingForCommandReply(_) => write!(f, "WaitingForCommandReply"),
+            LoopState::WaitingForCommandReply(..) => write!(f, "WaitingForCommandReply"),
         }
     }
 }
@@ -48,13 +91,33 @@ fn cancel_idle() -> RawCommand {
     RawCommand::new("noidle")
 }
 
+/// Reason for exiting a single iteration of the run loop.
+enum Exit {
+    /// The client was dropped, the connection should be closed.
+    ClientDropped,
+    /// The client requested closing the connection, which was done.
+    Closed,
+    /// The connection was closed by the server or failed.
+    ConnectionLost,
+}
+
 pub(super) async fn run_loop<C>(
-    mut connection: AsyncConnection<C>,
-    commands: Receiver<(RawCommandList, CommandResponder)>,
-    state_changes: StateChangesSender,
+    mut connection: AsyncConnection<Metered<C>>,
+    channels: Channels,
+    mut reconnect: Option<Reconnect<C>>,
 ) where
     C: AsyncRead + AsyncWrite + Unpin,
 {
+    let Channels {
+        commands,
+        state_changes,
+        messages,
+        keepalive,
+        observer,
+        events,
+        close,
+    } = channels;
+
     trace!("sending initial idle command");
     if let Err(e) = connection.send(idle()).await {
         error!(error = ?e, "failed to send initial idle command");
@@ -66,6 +129,11 @@ pub(super) async fn run_loop<C>(
         connection,
         commands,
         state_changes,
+        messages,
+        keepalive,
+        observer,
+        close,
+        reconnect: reconnect.is_some(),
     };
 
     trace!("entering run loop");
@@ -73,48 +141,106 @@ pub(super) async fn run_loop<C>(
     loop {
         let span = span!(Level::TRACE, "iteration", state = ?state.loop_state);
 
-        match run_loop_iteration(state).instrument(span).await {
-            Some(new_state) => state = new_state,
-            None => break,
+        match run_loop_iteration(&mut state).instrument(span).await {
+            Ok(()) => {}
+            Err(Exit::ClientDropped) => {
+                lifecycle::disconnected(&events, DisconnectReason::ClientDropped);
+                break;
+            }
+            Err(Exit::Closed) => {
+                lifecycle::disconnected(&events, DisconnectReason::Closed);
+                break;
+            }
+            Err(Exit::ConnectionLost) => {
+                lifecycle::disconnected(&events, DisconnectReason::ConnectionLost);
+
+                // Subscriptions don't survive reconnecting, so end the messages stream
+                end_messages(&state.messages);
+
+                let reconnect = match &mut reconnect {
+                    Some(reconnect) => reconnect,
+                    None => break,
+                };
+
+                match reconnect
+                    .run(&mut state.commands, &mut state.close, &events)
+                    .await
+                {
+                    Ok(connection) => {
+                        events.send_replace(ConnectionEvent::Connected {
+                            protocol_version: Arc::from(connection.protocol_version()),
+                        });
+                        state.connection = connection;
+                        state.loop_state = LoopState::Idling;
+
+                        trace!("sending idle command after reconnecting");
+                        if let Err(e) = state.connection.send(idle()).await {
+                            error!(error = ?e, "failed to send idle command after reconnecting");
+                            let _ = state.state_changes.send(Err(e.into()));
+                        }
+                    }
+                    Err(reason) => {
+                        lifecycle::disconnected(&events, reason);
+                        break;
+                    }
+                }
+            }
         }
     }
 
+    end_messages(&state.messages);
+
     trace!("exited run_loop");
 }
 
+/// End the messages stream, if one is active.
+fn end_messages(messages: &MessagesSender) {
+    messages.lock().unwrap().take();
+}
+
 /// Time to wait for another command to send before starting the idle loop.
 const NEXT_COMMAND_IDLE_TIMEOUT: Duration = Duration::from_millis(100);
 
-async fn run_loop_iteration<C>(mut state: State<C>) -> Option<State<C>>
+async fn run_loop_iteration<C>(state: &mut State<C>) -> Result<(), Exit>
 where
     C: AsyncRead + AsyncWrite + Unpin,
 {
-    match state.loop_state {
+    match mem::replace(&mut state.loop_state, LoopState::Idling) {
         LoopState::Idling => {
             // We are idling (the last command sent to the server was an IDLE).
 
             // Wait for either a command to send or a message from the server, which would be a
             // state change notification.
+            let keepalive = *state.keepalive.borrow();
+            let keepalive_timer = sleep(keepalive.unwrap_or_default());
+
             tokio::select! {
+                _ = keepalive_timer, if keepalive.is_some() => {
+                    ping(state, keepalive.unwrap_or_default()).await?;
+                }
+                Ok(()) = state.keepalive.changed() => trace!("keepalive interval changed"),
+                Ok(()) = state.close.changed() => {
+                    // Reject new commands, but keep handling the already queued ones. The
+                    // connection is closed once the queue is empty.
+                    trace!("closing requested");
+                    state.commands.close();
+                }
                 response = state.connection.receive() => {
                     match response {
                         Ok(Some(res)) => {
-                            if let Some(state_change) = response_to_subsystem(res).transpose() {
-                                trace!(?state_change);
-                                let _ = state.state_changes.send(state_change);
-                            }
+                            handle_state_change(state, res).await?;
 
                             if let Err(e) = state.connection.send(idle()).await {
                                 error!(error = ?e, "failed to start idling after state change");
                                 let _ = state.state_changes.send(Err(e.into()));
-                                return None;
+                                return Err(Exit::ConnectionLost);
                             }
                         }
-                        Ok(None) => return None, // The connection was closed
+                        Ok(None) => return Err(Exit::ConnectionLost), // The connection was closed
                         Err(e) => {
                             error!(error = ?e, "state change error");
                             let _ = state.state_changes.send(Err(e.into()));
-                            return None;
+                            return Err(Exit::ConnectionLost);
                         }
                     }
                 }
@@ -122,54 +248,78 @@ where
                     // A command was received or the commands channel was dropped. The latter
                     // is an indicator for us to close the connection.
 
-                    let (command, responder) = command?;
+                    let (command, responder) = match command {
+                        Some(command) => command,
+                        None => return Err(close(state, true).await),
+                    };
                     trace!(?command, "command received");
 
                     // Cancel currently ongoing idle
                     if let Err(e) = state.connection.send(cancel_idle()).await {
                         error!(error = ?e, "failed to cancel idle prior to sending command");
-                        let _ = responder.send(Err(e.into()));
-                        return None;
+                        let _ = responder.send(Err(state.lost_command_error(e.into())));
+                        return Err(Exit::ConnectionLost);
                     }
 
                     // Response to CANCEL_IDLE above
                     match state.connection.receive().await {
-                        Ok(None) => return None,
-                        Ok(Some(res)) => {
-                            if let Some(state_change) = response_to_subsystem(res).transpose() {
-                                trace!(?state_change);
-                                let _ = state.state_changes.send(state_change);
-                            }
+                        Ok(None) => {
+                            let error = state.lost_command_error(CommandError::ConnectionClosed);
+                            let _ = responder.send(Err(error));
+                            return Err(Exit::ConnectionLost);
                         }
+                        Ok(Some(res)) => handle_state_change(state, res).await?,
                         Err(e) => {
                             error!(error = ?e, "state change error prior to sending command");
-                            let _ = responder.send(Err(e.into()));
-                            return None;
+                            let _ = responder.send(Err(state.lost_command_error(e.into())));
+                            return Err(Exit::ConnectionLost);
                         }
                     }
 
                     // Actually send the command. This sets the state for the next loop
                     // iteration.
-                    match state.connection.send_list(command).await {
-                        Ok(_) => state.loop_state = LoopState::WaitingForCommandReply(responder),
-                        Err(e) => {
-                            error!(error = ?e, "failed to send command");
-                            let _ = responder.send(Err(e.into()));
-                            return None;
-                        }
-                    }
-
-                    trace!("command sent successfully");
+                    send_command(state, command, responder).await?;
                 }
             }
         }
-        LoopState::WaitingForCommandReply(responder) => {
+        LoopState::WaitingForCommandReply(responder, sent_at) => {
             // We're waiting for the response to the command associated with `responder`.
 
-            let response = state.connection.receive().await.transpose()?;
+            let response = match state.connection.receive().await {
+                Ok(Some(response)) => Ok(response),
+                // The server closed the connection instead of responding, e.g. after `kill`
+                Ok(None) => Err(CommandError::ClosedByServer),
+                Err(MpdProtocolError::Io(e)) if e.kind() == io::ErrorKind::UnexpectedEof => {
+                    Err(CommandError::ClosedByServer)
+                }
+                Err(e) => Err(e.into()),
+            };
+            // Malformed responses are skipped by the connection, but IO errors are fatal
+            let failed = matches!(
+                response,
+                Err(CommandError::ClosedByServer | CommandError::Protocol(MpdProtocolError::Io(_)))
+            );
             trace!("response to command received");
 
-            let _ = responder.send(response.map_err(Into::into));
+            let success = matches!(&response, Ok(r) if !r.is_error());
+            state
+                .observer
+                .notify(|o| o.response_received(sent_at.elapsed(), success));
+
+            // A command the server closed the connection after (e.g. `kill`) was executed, so only
+            // report IO errors as lost
+            let response = match response {
+                Err(e @ CommandError::Protocol(MpdProtocolError::Io(_))) => {
+                    Err(state.lost_command_error(e))
+                }
+                response => response,
+            };
+
+            let _ = responder.send(response);
+
+            if failed {
+                return Err(Exit::ConnectionLost);
+            }
 
             let next_command = timeout(NEXT_COMMAND_IDLE_TIMEOUT, state.commands.recv());
 
@@ -177,16 +327,9 @@ where
             match next_command.await {
                 Ok(Some((command, responder))) => {
                     trace!(?command, "next command immediately available");
-                    match state.connection.send_list(command).await {
-                        Ok(_) => state.loop_state = LoopState::WaitingForCommandReply(responder),
-                        Err(e) => {
-                            error!(error = ?e, "failed to send command");
-                            let _ = responder.send(Err(e.into()));
-                            return None;
-                        }
-                    }
+                    send_command(state, command, responder).await?;
                 }
-                Ok(None) => return None,
+                Ok(None) => return Err(close(state, false).await),
                 Err(_) => {
                     trace!("reached next command timeout, idling");
 
@@ -195,14 +338,199 @@ where
                     if let Err(e) = state.connection.send(idle()).await {
                         error!(error = ?e, "failed to start idling after receiving command response");
                         let _ = state.state_changes.send(Err(e.into()));
-                        return None;
+                        return Err(Exit::ConnectionLost);
                     }
                 }
             }
         }
     }
 
-    Some(state)
+    Ok(())
+}
+
+/// Handle all clients being gone, which is either because they were dropped or because closing
+/// the connection was requested.
+async fn close<C>(state: &mut State<C>, idling: bool) -> Exit
+where
+    C: AsyncRead + AsyncWrite + Unpin,
+{
+    if !*state.close.borrow() {
+        return Exit::ClientDropped;
+    }
+
+    trace!("closing connection");
+
+    if idling {
+        if let Err(e) = state.connection.send(cancel_idle()).await {
+            error!(error = ?e, "failed to cancel idle prior to closing");
+            return Exit::Closed;
+        }
+
+        match state.connection.receive().await {
+            Ok(Some(res)) => {
+                if handle_state_change(state, res).await.is_err() {
+                    return Exit::Closed;
+                }
+            }
+            Ok(None) => return Exit::Closed,
+            Err(e) => {
+                error!(error = ?e, "error while cancelling idle prior to closing");
+                return Exit::Closed;
+            }
+        }
+    }
+
+    // The server closes the connection without a response
+    if let Err(e) = state.connection.send(RawCommand::new("close")).await {
+        error!(error = ?e, "failed to send close command");
+    }
+
+    Exit::Closed
+}
+
+/// Send a command issued through the client, and start waiting for its response.
+async fn send_command<C>(
+    state: &mut State<C>,
+    command: RawCommandList,
+    responder: CommandResponder,
+) -> Result<(), Exit>
+where
+    C: AsyncRead + AsyncWrite + Unpin,
+{
+    state.observer.notify(|o| o.command_sent(&command));
+
+    match state.connection.send_list(command).await {
+        Ok(_) => {
+            trace!("command sent successfully");
+            state.loop_state = LoopState::WaitingForCommandReply(responder, Instant::now());
+            Ok(())
+        }
+        Err(e) => {
+            error!(error = ?e, "failed to send command");
+            let _ = responder.send(Err(state.lost_command_error(e.into())));
+            Err(Exit::ConnectionLost)
+        }
+    }
+}
+
+/// Leave the idle state to ping the server, then start idling again.
+///
+/// The connection is considered lost if the server doesn't respond within `deadline`.
+async fn ping<C>(state: &mut State<C>, deadline: Duration) -> Result<(), Exit>
+where
+    C: AsyncRead + AsyncWrite + Unpin,
+{
+    trace!("sending keepalive ping");
+
+    let exchange = async {
+        // The response to cancelling the idle may contain a state change
+        let res = keepalive_command(state, cancel_idle()).await?;
+        handle_state_change(state, res).await?;
+
+        keepalive_command(state, RawCommand::new("ping")).await
+    };
+
+    match timeout(deadline, exchange).await {
+        Ok(res) => {
+            res?;
+        }
+        Err(_) => {
+            error!("keepalive ping timed out");
+            return Err(Exit::ConnectionLost);
+        }
+    }
+
+    if let Err(e) = state.connection.send(idle()).await {
+        error!(error = ?e, "failed to start idling after keepalive ping");
+        let _ = state.state_changes.send(Err(e.into()));
+        return Err(Exit::ConnectionLost);
+    }
+
+    Ok(())
+}
+
+async fn keepalive_command<C>(
+    state: &mut State<C>,
+    command: RawCommand,
+) -> Result<RawResponse, Exit>
+where
+    C: AsyncRead + AsyncWrite + Unpin,
+{
+    if let Err(e) = state.connection.send(command).await {
+        error!(error = ?e, "failed to send keepalive ping");
+        let _ = state.state_changes.send(Err(e.into()));
+        return Err(Exit::ConnectionLost);
+    }
+
+    match state.connection.receive().await {
+        Ok(Some(res)) => Ok(res),
+        Ok(None) => Err(Exit::ConnectionLost),
+        Err(e) => {
+            error!(error = ?e, "error while sending keepalive ping");
+            let _ = state.state_changes.send(Err(e.into()));
+            Err(Exit::ConnectionLost)
+        }
+    }
+}
+
+/// Forward the state change contained in the given response to the client, reading the pending
+/// channel messages if it is a [`Message`](Subsystem::Message) change.
+async fn handle_state_change<C>(state: &mut State<C>, res: RawResponse) -> Result<(), Exit>
+where
+    C: AsyncRead + AsyncWrite + Unpin,
+{
+    if let Some(state_change) = response_to_subsystem(res).transpose() {
+        trace!(?state_change);
+
+        if matches!(state_change, Ok(Subsystem::Message)) {
+            read_messages(state).await?;
+        }
+
+        let _ = state.state_changes.send(state_change);
+    }
+
+    Ok(())
+}
+
+/// Read the pending channel messages and forward them to the messages stream, if there is one.
+async fn read_messages<C>(state: &mut State<C>) -> Result<(), Exit>
+where
+    C: AsyncRead + AsyncWrite + Unpin,
+{
+    let sender = match &*state.messages.lock().unwrap() {
+        Some(sender) if !sender.is_closed() => sender.clone(),
+        _ => return Ok(()),
+    };
+
+    trace!("reading channel messages");
+    if let Err(e) = state.connection.send(RawCommand::new("readmessages")).await {
+        error!(error = ?e, "failed to send readmessages command");
+        let _ = state.state_changes.send(Err(e.into()));
+        return Err(Exit::ConnectionLost);
+    }
+
+    let messages = match state.connection.receive().await {
+        Ok(Some(res)) => res
+            .single_frame()
+            .map_err(|e| warn!(error = ?e, "failed to read channel messages"))
+            .and_then(|frame| {
+                Vec::<ChannelMessage>::from_frame(frame)
+                    .map_err(|e| warn!(error = ?e, "invalid channel messages"))
+            })
+            .unwrap_or_default(),
+        Ok(None) => return Err(Exit::ConnectionLost),
+        Err(e) => {
+            error!(error = ?e, "error while reading channel messages");
+            let _ = state.state_changes.send(Err(e.into()));
+            return Err(Exit::ConnectionLost);
+        }
+    };
+
+    for message in messages {
+        let _ = sender.send(message);
+    }
+
+    Ok(())
 }
 
 fn response_to_subsystem(res: RawResponse) -> Result<Option<Subsystem>, StateChangeError> {
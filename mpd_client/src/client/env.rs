@@ -0,0 +1,158 @@
+//! Connection settings from the environment, compatible with `mpc` and `libmpdclient`.
+
+use std::env;
+use std::io;
+use std::path::PathBuf;
+use std::time::Duration;
+
+/// Host used when `MPD_HOST` is not set.
+const DEFAULT_HOST: &str = "localhost";
+
+/// Port used when `MPD_PORT` is not set.
+const DEFAULT_PORT: u16 = 6600;
+
+/// Connection settings read from `MPD_HOST`, `MPD_PORT` and `MPD_TIMEOUT`.
+#[derive(Debug, PartialEq, Eq)]
+pub(super) struct EnvSettings {
+    pub(super) target: Target,
+    pub(super) password: Option<String>,
+    pub(super) timeout: Option<Duration>,
+}
+
+/// Where to connect to.
+#[derive(Debug, PartialEq, Eq)]
+pub(super) enum Target {
+    Tcp { host: String, port: u16 },
+    Unix(PathBuf),
+}
+
+impl EnvSettings {
+    /// Read the settings from the environment of the current process.
+    pub(super) fn from_env() -> io::Result<Self> {
+        Self::parse(
+            env::var("MPD_HOST").ok(),
+            env::var("MPD_PORT").ok(),
+            env::var("MPD_TIMEOUT").ok(),
+        )
+    }
+
+    fn parse(
+        host: Option<String>,
+        port: Option<String>,
+        timeout: Option<String>,
+    ) -> io::Result<Self> {
+        let host = host.filter(|h| !h.is_empty());
+        let (password, host) = match host {
+            // A leading `@` denotes an abstract socket, not an empty password, and a leading
+            // slash denotes a socket path
+            Some(host) if host.starts_with('@') || host.starts_with('/') => (None, host),
+            Some(host) => match host.split_once('@') {
+                Some((password, host)) => (Some(password.to_owned()), host.to_owned()),
+                None => (None, host),
+            },
+            None => (None, String::from(DEFAULT_HOST)),
+        };
+
+        let target = if host.starts_with('/') {
+            Target::Unix(PathBuf::from(host))
+        } else if host.starts_with('@') {
+            return Err(invalid_input("abstract sockets are not supported"));
+        } else {
+            let port = match port {
+                Some(port) => port
+                    .parse()
+                    .map_err(|_| invalid_input("invalid value for MPD_PORT"))?,
+                None => DEFAULT_PORT,
+            };
+
+            Target::Tcp { host, port }
+        };
+
+        let timeout = match timeout {
+            Some(timeout) => {
+                let seconds = timeout
+                    .parse()
+                    .map_err(|_| invalid_input("invalid value for MPD_TIMEOUT"))?;
+                Some(Duration::from_secs(seconds))
+            }
+            None => None,
+        };
+
+        Ok(EnvSettings {
+            target,
+            password,
+            timeout,
+        })
+    }
+}
+
+fn invalid_input(message: &'static str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidInput, message)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(host: Option<&str>, port: Option<&str>) -> io::Result<EnvSettings> {
+        EnvSettings::parse(host.map(String::from), port.map(String::from), None)
+    }
+
+    fn tcp(host: &str, port: u16) -> Target {
+        Target::Tcp {
+            host: String::from(host),
+            port,
+        }
+    }
+
+    #[test]
+    fn defaults() {
+        let settings = parse(None, None).unwrap();
+
+        assert_eq!(settings.target, tcp("localhost", 6600));
+        assert_eq!(settings.password, None);
+        assert_eq!(settings.timeout, None);
+    }
+
+    #[test]
+    fn host_and_port() {
+        let settings = parse(Some("music.local"), Some("6601")).unwrap();
+        assert_eq!(settings.target, tcp("music.local", 6601));
+
+        assert!(parse(Some("music.local"), Some("foo")).is_err());
+    }
+
+    #[test]
+    fn password() {
+        let settings = parse(Some("hunter2@music.local"), None).unwrap();
+        assert_eq!(settings.target, tcp("music.local", 6600));
+        assert_eq!(settings.password.as_deref(), Some("hunter2"));
+
+        let settings = parse(Some("hunter2@/run/mpd/socket"), None).unwrap();
+        assert_eq!(
+            settings.target,
+            Target::Unix(PathBuf::from("/run/mpd/socket"))
+        );
+        assert_eq!(settings.password.as_deref(), Some("hunter2"));
+    }
+
+    #[test]
+    fn socket_path() {
+        let settings = parse(Some("/run/mpd/socket"), Some("1234")).unwrap();
+        assert_eq!(
+            settings.target,
+            Target::Unix(PathBuf::from("/run/mpd/socket"))
+        );
+        assert_eq!(settings.password, None);
+
+        assert!(parse(Some("@mpd"), None).is_err());
+    }
+
+    #[test]
+    fn timeout() {
+        let settings = EnvSettings::parse(None, None, Some(String::from("10"))).unwrap();
+        assert_eq!(settings.timeout, Some(Duration::from_secs(10)));
+
+        assert!(EnvSettings::parse(None, None, Some(String::from("soon"))).is_err());
+    }
+}
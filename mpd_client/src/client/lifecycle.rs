@@ -0,0 +1,54 @@
+use tokio::sync::watch;
+
+use std::sync::Arc;
+
+/// Sender for connection lifecycle events, owned by the run loop.
+pub(super) type EventsSender = watch::Sender<ConnectionEvent>;
+
+/// Lifecycle events of the connection, returned by [`Client::connection_events`].
+///
+/// These are separate from the [state changes](crate::state_changes) emitted by MPD, and describe
+/// the connection itself. The latest event always reflects the current state of the connection.
+///
+/// [`Client::connection_events`]: super::Client::connection_events
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ConnectionEvent {
+    /// The connection is established, using the given protocol version.
+    Connected {
+        /// The protocol version reported by the server.
+        protocol_version: Arc<str>,
+    },
+    /// The connection was closed.
+    ///
+    /// If [reconnecting](super::Client::connect_with_reconnect) is enabled and the connection was
+    /// lost, this is followed by [`Reconnecting`](ConnectionEvent::Reconnecting) events.
+    Disconnected {
+        /// Why the connection was closed.
+        reason: DisconnectReason,
+    },
+    /// An attempt to reestablish the connection is being made.
+    Reconnecting {
+        /// The number of the current attempt, starting at 1.
+        attempt: u32,
+    },
+}
+
+/// Reason for a [`ConnectionEvent::Disconnected`] event.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum DisconnectReason {
+    /// The connection was closed using [`Client::close`](super::Client::close).
+    Closed,
+    /// All clients were dropped.
+    ClientDropped,
+    /// The server closed the connection, or an error occurred on it.
+    ConnectionLost,
+    /// Reconnecting failed, either because the maximum number of attempts was reached or because
+    /// the password was rejected.
+    ReconnectFailed,
+}
+
+pub(super) fn disconnected(events: &EventsSender, reason: DisconnectReason) {
+    events.send_replace(ConnectionEvent::Disconnected { reason });
+}
@@ -0,0 +1,45 @@
+use futures_core::stream::Stream;
+use tokio::sync::mpsc::{self, UnboundedReceiver, UnboundedSender};
+
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll};
+
+use crate::commands::responses::ChannelMessage;
+
+/// Sender for automatically read messages, shared between the client and the run loop.
+pub(super) type MessagesSender = Arc<Mutex<Option<UnboundedSender<ChannelMessage>>>>;
+
+/// Stream of messages received on subscribed channels, returned by [`Client::messages`].
+///
+/// While this stream is alive, the client reads the messages from the server whenever a
+/// [`Message`] state change is received. The stream ending (yielding `None`) indicates that the
+/// connection was closed or lost, or a newer stream was requested.
+///
+/// Subscriptions don't survive [reconnecting](super::Client::connect_with_reconnect), so the
+/// stream also ends when the connection is lost. Subscribe again and request a new stream after
+/// the client reconnected.
+///
+/// [`Client::messages`]: super::Client::messages
+/// [`Message`]: crate::state_changes::Subsystem::Message
+#[derive(Debug)]
+pub struct Messages {
+    rx: UnboundedReceiver<ChannelMessage>,
+}
+
+impl Messages {
+    pub(super) fn register(sender: &MessagesSender) -> Self {
+        let (tx, rx) = mpsc::unbounded_channel();
+        *sender.lock().unwrap() = Some(tx);
+
+        Self { rx }
+    }
+}
+
+impl Stream for Messages {
+    type Item = ChannelMessage;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.rx.poll_recv(cx)
+    }
+}
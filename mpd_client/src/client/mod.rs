@@ -1,29 +1,63 @@
 //! The client implementation.
 
+mod album_art;
 mod connection;
+mod env;
+mod lifecycle;
+mod messages;
+mod observer;
+mod ratings;
+mod reconnect;
+mod socks;
+mod state_tracker;
 
 use mpd_protocol::{AsyncConnection, Response as RawResponse};
+#[cfg(unix)]
+use tokio::net::UnixStream;
 use tokio::{
     io::{AsyncRead, AsyncWrite},
+    net::{TcpStream, ToSocketAddrs},
     sync::{
         mpsc::{self, Sender},
-        oneshot,
+        oneshot, watch,
     },
+    time::timeout,
 };
 use tracing::{debug, error, span, trace, warn, Instrument, Level};
 
 use std::error::Error;
 use std::fmt;
+use std::future::Future;
 use std::io;
-use std::sync::Arc;
+#[cfg(unix)]
+use std::path::Path;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
 
 use crate::commands::{self as cmds, responses::Response, Command, CommandList};
 use crate::errors::CommandError;
-use crate::raw::{Frame, MpdProtocolError, RawCommand, RawCommandList};
-use crate::state_changes::StateChanges;
+use crate::raw::{ErrorCode, Frame, MpdProtocolError, RawCommand, RawCommandList};
+use crate::state_changes::{StateChanges, Subsystem};
+use env::{EnvSettings, Target};
+use messages::MessagesSender;
+use observer::{Metered, ObserverSlot};
+use reconnect::Reconnect;
+
+pub use album_art::AlbumArtStream;
+pub use lifecycle::{ConnectionEvent, DisconnectReason};
+pub use messages::Messages;
+pub use observer::ConnectionObserver;
+pub use ratings::{Rating, RatingError, Ratings};
+pub use reconnect::ReconnectOptions;
+pub use socks::Socks5Proxy;
+pub use state_tracker::StateTracker;
 
 type CommandResponder = oneshot::Sender<Result<RawResponse, CommandError>>;
 
+/// Binary limit requested when loading album art which doesn't fit into a single response.
+const ALBUM_ART_BINARY_LIMIT: usize = 1024 * 1024;
+
 /// Components of a connection.
 ///
 /// This contains a [`Client`] which you can use to issue commands, and a [`StateChanges`] value,
@@ -38,10 +72,24 @@ pub type Connection = (Client, StateChanges);
 /// # Connection management
 ///
 /// Dropping the last clone of a particular `Client` will close the connection automatically.
+///
+/// # Cancellation
+///
+/// The futures returned by the methods sending commands are cancel safe. Dropping one of them
+/// before it completes does not desynchronize the connection: If the command was already sent,
+/// its response is still read and discarded by the connection task, and responses to later
+/// commands are delivered to the correct caller.
 #[derive(Clone, Debug)]
 pub struct Client {
     commands_sender: Sender<(RawCommandList, CommandResponder)>,
     protocol_version: Arc<str>,
+    partition: Arc<Mutex<Option<String>>>,
+    messages: MessagesSender,
+    keepalive: Arc<watch::Sender<Option<Duration>>>,
+    command_timeout: Arc<Mutex<Option<Duration>>>,
+    observer: ObserverSlot,
+    connection_events: watch::Receiver<ConnectionEvent>,
+    close: Arc<watch::Sender<bool>>,
 }
 
 impl Client {
@@ -61,10 +109,145 @@ impl Client {
     where
         C: AsyncRead + AsyncWrite + Unpin + Send + 'static,
     {
-        do_connect(connection, None).await.map_err(|e| match e {
-            ConnectWithPasswordError::ProtocolError(e) => e,
-            ConnectWithPasswordError::IncorrectPassword => unreachable!(),
-        })
+        do_connect(connection, None, None)
+            .await
+            .map_err(|e| match e {
+                ConnectWithPasswordError::ProtocolError(e) => e,
+                ConnectWithPasswordError::IncorrectPassword => unreachable!(),
+            })
+    }
+
+    /// Connect to the MPD server listening on the given TCP address.
+    ///
+    /// This is a shorthand for establishing a [`TcpStream`] and passing it to [`Client::connect`].
+    ///
+    /// # Panics
+    ///
+    /// Since this spawns a task internally, this will panic when called outside a Tokio runtime.
+    ///
+    /// # Errors
+    ///
+    /// This will return an error if establishing the TCP connection or sending the initial
+    /// commands fails.
+    pub async fn connect_tcp<A>(address: A) -> Result<Connection, MpdProtocolError>
+    where
+        A: ToSocketAddrs,
+    {
+        let stream = TcpStream::connect(address).await?;
+        Client::connect(stream).await
+    }
+
+    /// Connect to the MPD server at the given host and port through a SOCKS5 proxy, optionally
+    /// sending a password.
+    ///
+    /// The host name is resolved by the proxy. To reconnect automatically, pass
+    /// [`Socks5Proxy::connect`] to [`Client::connect_with_reconnect`] instead.
+    ///
+    /// # Panics
+    ///
+    /// Since this spawns a task internally, this will panic when called outside a Tokio runtime.
+    ///
+    /// # Errors
+    ///
+    /// This will return an error if connecting to the proxy fails, if the proxy fails to connect
+    /// to the server, if sending the initial commands fails, or if the password is incorrect.
+    pub async fn connect_socks5(
+        proxy: &Socks5Proxy,
+        host: &str,
+        port: u16,
+        password: Option<&str>,
+    ) -> Result<Connection, ConnectWithPasswordError> {
+        let stream = proxy
+            .connect(host, port)
+            .await
+            .map_err(MpdProtocolError::from)?;
+        Client::connect_with_password_opt(stream, password).await
+    }
+
+    /// Connect to the MPD server listening on the Unix socket at the given path.
+    ///
+    /// This is a shorthand for establishing a [`UnixStream`] and passing it to
+    /// [`Client::connect`].
+    ///
+    /// # Panics
+    ///
+    /// Since this spawns a task internally, this will panic when called outside a Tokio runtime.
+    ///
+    /// # Errors
+    ///
+    /// This will return an error if connecting to the socket or sending the initial commands
+    /// fails.
+    #[cfg(unix)]
+    pub async fn connect_unix<P>(path: P) -> Result<Connection, MpdProtocolError>
+    where
+        P: AsRef<Path>,
+    {
+        let stream = UnixStream::connect(path).await?;
+        Client::connect(stream).await
+    }
+
+    /// Connect to the MPD server specified by the environment, in the same way as `mpc`.
+    ///
+    /// The following environment variables are used:
+    ///
+    ///  - `MPD_HOST`: The host name or the path of a Unix socket (starting with a `/`). May be
+    ///    prefixed with a password followed by an `@` (`password@host`). Defaults to `localhost`.
+    ///  - `MPD_PORT`: The TCP port. Defaults to 6600.
+    ///  - `MPD_TIMEOUT`: Timeout for establishing the connection, in seconds. By default, there is
+    ///    no timeout.
+    ///
+    /// # Panics
+    ///
+    /// Since this spawns a task internally, this will panic when called outside a Tokio runtime.
+    ///
+    /// # Errors
+    ///
+    /// This will return an error if the environment variables contain invalid values, if
+    /// establishing the connection fails or times out, or if the password is incorrect.
+    pub async fn connect_from_env() -> Result<Connection, ConnectWithPasswordError> {
+        let EnvSettings {
+            target,
+            password,
+            timeout: connect_timeout,
+        } = EnvSettings::from_env().map_err(MpdProtocolError::from)?;
+
+        debug!(?target, connect_timeout = ?connect_timeout, "connecting using environment");
+
+        let connect = async {
+            match target {
+                Target::Tcp { host, port } => {
+                    let stream = TcpStream::connect((host.as_str(), port))
+                        .await
+                        .map_err(MpdProtocolError::from)?;
+                    do_connect(stream, password.as_deref(), None).await
+                }
+                #[cfg(unix)]
+                Target::Unix(path) => {
+                    let stream = UnixStream::connect(path)
+                        .await
+                        .map_err(MpdProtocolError::from)?;
+                    do_connect(stream, password.as_deref(), None).await
+                }
+                #[cfg(not(unix))]
+                Target::Unix(_) => Err(MpdProtocolError::from(io::Error::new(
+                    io::ErrorKind::Unsupported,
+                    "unix sockets are not supported on this platform",
+                ))
+                .into()),
+            }
+        };
+
+        match connect_timeout {
+            Some(duration) => timeout(duration, connect).await.unwrap_or_else(|_| {
+                error!("timed out while connecting");
+                Err(MpdProtocolError::from(io::Error::new(
+                    io::ErrorKind::TimedOut,
+                    "timed out while connecting",
+                ))
+                .into())
+            }),
+            None => connect.await,
+        }
     }
 
     /// Connect to the password-protected MPD server using the given connection and password.
@@ -87,7 +270,7 @@ impl Client {
     where
         C: AsyncRead + AsyncWrite + Unpin + Send + 'static,
     {
-        do_connect(connection, Some(password)).await
+        do_connect(connection, Some(password), None).await
     }
 
     /// Connect to the possibly password-protected MPD server using the given connection and password.
@@ -110,7 +293,67 @@ impl Client {
     where
         C: AsyncRead + AsyncWrite + Unpin + Send + 'static,
     {
-        do_connect(connection, password).await
+        do_connect(connection, password, None).await
+    }
+
+    /// Connect to the MPD server, automatically reconnecting if the connection is lost.
+    ///
+    /// The `connect` function is called to establish the initial connection, and again every time
+    /// the client reconnects, with delays between failed attempts as configured in `options`. The
+    /// handshake (including sending the `password`, if any) is repeated for every new connection.
+    ///
+    /// While the client is reconnecting, commands fail with [`CommandError::Reconnecting`]. This
+    /// includes the command that was in progress when the connection failed, but not one after
+    /// which the server closed the connection (such as [`Kill`](crate::commands::Kill)), which
+    /// still fails with [`CommandError::ClosedByServer`]. If the client gives up on reconnecting,
+    /// or the password is rejected on a new connection, the client is closed as it would be
+    /// without reconnecting.
+    ///
+    /// Note that state changes which occur while the connection is lost are not reported, and
+    /// the [protocol version](Client::protocol_version) is that of the initial connection.
+    ///
+    /// ```no_run
+    /// use mpd_client::{Client, ReconnectOptions};
+    /// use tokio::net::TcpStream;
+    ///
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// let (client, state_changes) = Client::connect_with_reconnect(
+    ///     || Box::pin(TcpStream::connect("localhost:6600")),
+    ///     None,
+    ///     ReconnectOptions::new().max_attempts(10),
+    /// )
+    /// .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Since this spawns a task internally, this will panic when called outside a Tokio runtime.
+    ///
+    /// # Errors
+    ///
+    /// This will return an error if establishing the initial connection fails, or if the password
+    /// is incorrect.
+    pub async fn connect_with_reconnect<F, C>(
+        mut connect: F,
+        password: Option<String>,
+        options: ReconnectOptions,
+    ) -> Result<Connection, ConnectWithPasswordError>
+    where
+        F: FnMut() -> Pin<Box<dyn Future<Output = io::Result<C>> + Send>> + Send + 'static,
+        C: AsyncRead + AsyncWrite + Unpin + Send + 'static,
+    {
+        let io = connect().await.map_err(MpdProtocolError::from)?;
+        let reconnect = Reconnect {
+            connector: Box::new(connect),
+            password: password.clone(),
+            options,
+            partition: Arc::default(),
+            observer: ObserverSlot::default(),
+        };
+
+        do_connect(io, password.as_deref(), Some(reconnect)).await
     }
 
     /// Send a [command].
@@ -127,12 +370,43 @@ impl Client {
     where
         C: Command,
     {
+        self.check_protocol_version(cmd.min_protocol_version())?;
+
         let command = cmd.into_command();
         let frame = self.raw_command(command).await?;
 
         Ok(Response::from_frame(frame)?)
     }
 
+    /// Send a [command], failing with [`CommandError::Timeout`] if the server doesn't respond
+    /// within the given duration.
+    ///
+    /// This overrides the [default timeout](Client::set_command_timeout) for this command.
+    ///
+    /// # Errors
+    ///
+    /// This returns errors in the same conditions as [`Client::command`].
+    ///
+    /// [command]: super::commands
+    pub async fn command_with_timeout<C>(
+        &self,
+        cmd: C,
+        timeout: Duration,
+    ) -> Result<C::Response, CommandError>
+    where
+        C: Command,
+    {
+        self.check_protocol_version(cmd.min_protocol_version())?;
+
+        let command = RawCommandList::new(cmd.into_command());
+        let frame = self
+            .send_with_timeout(command, Some(timeout))
+            .await?
+            .single_frame()?;
+
+        Ok(Response::from_frame(frame)?)
+    }
+
     /// Send the given command list, and return the (typed) responses.
     ///
     /// # Errors
@@ -143,6 +417,8 @@ impl Client {
     where
         L: CommandList,
     {
+        self.check_protocol_version(list.min_protocol_version())?;
+
         let frames = match list.into_raw_command_list() {
             Some(cmds) => self.raw_command_list(cmds).await?,
             None => Vec::new(),
@@ -208,8 +484,10 @@ impl Client {
     /// [`albumart`](cmds::AlbumArt) command.
     ///
     /// **Note**: Due to the default binary size limit of MPD being quite low, loading larger art
-    /// will issue many commands and can be slow. Consider increasing the
-    /// [binary size limit][cmds::SetBinaryLimit].
+    /// would issue many commands. If the art doesn't fit into the first response, the
+    /// [binary size limit][cmds::SetBinaryLimit] is therefore raised to 1 MiB on servers which
+    /// support it (protocol version 0.22.4 and later). This also affects other binary responses
+    /// on the same connection.
     ///
     /// # Return value
     ///
@@ -230,68 +508,307 @@ impl Client {
 
         debug!("loading album art");
 
-        let mut out = Vec::new();
-        let mut expected_size = 0;
-        let mut embedded = false;
-        let mut mime = None;
+        let (first, embedded) = match self.first_album_art_chunk(uri).await? {
+            Some(first) => first,
+            None => return Ok(None),
+        };
+
+        let expected_size = first.size;
+        let mime = first.mime;
+        let mut out = Vec::with_capacity(expected_size);
+        out.extend_from_slice(&first.data);
+
+        while out.len() < expected_size {
+            let resp = if embedded {
+                self.command(cmds::AlbumArtEmbedded::new(uri.to_owned()).offset(out.len()))
+                    .await?
+            } else {
+                self.command(cmds::AlbumArt::new(uri.to_owned()).offset(out.len()))
+                    .await?
+            };
+
+            if let Some(resp) = resp {
+                let data = resp.data();
+                trace!(received = data.len(), progress = out.len());
+                out.extend_from_slice(data);
+            } else {
+                warn!(progress = out.len(), "incomplete cover art response");
+                return Ok(None);
+            }
+        }
+
+        debug!(length = expected_size, "finished loading");
+
+        Ok(Some((out, mime)))
+    }
+
+    /// Load album art for the given URI as a stream of chunks.
+    ///
+    /// This behaves like [`Client::album_art`], but instead of assembling the complete data in
+    /// memory, the returned [`AlbumArtStream`] loads and yields the chunks as they are consumed.
+    /// This is useful for decoding or writing very large images incrementally.
+    ///
+    /// # Return value
+    ///
+    /// A return value of `None` indicates that no album art for the given URI was found.
+    ///
+    /// # Errors
+    ///
+    /// This returns errors in the same conditions as [`Client::command`], both when loading the
+    /// first chunk and from the stream.
+    pub async fn album_art_stream(
+        &self,
+        uri: &str,
+    ) -> Result<Option<AlbumArtStream>, CommandError> {
+        let span = span!(Level::DEBUG, "album_art_stream", ?uri);
+        let _enter = span.enter();
+
+        Ok(self
+            .first_album_art_chunk(uri)
+            .await?
+            .map(|(first, embedded)| {
+                AlbumArtStream::new(self.clone(), uri.to_owned(), first, embedded)
+            }))
+    }
 
+    /// Load the first chunk of album art, preferring embedded data. Also returns whether the data
+    /// is embedded.
+    async fn first_album_art_chunk(
+        &self,
+        uri: &str,
+    ) -> Result<Option<(cmds::responses::AlbumArt, bool)>, CommandError> {
         match self
             .command(cmds::AlbumArtEmbedded::new(uri.to_owned()))
             .await
         {
             Ok(Some(resp)) => {
-                expected_size = resp.size;
-                out.reserve(expected_size);
-                out.extend_from_slice(resp.data());
-                embedded = true;
-                mime = resp.mime;
-                debug!(length = resp.size, ?mime, "found embedded album art");
+                debug!(length = resp.size, mime = ?resp.mime, "found embedded album art");
+                self.raise_binary_limit(&resp).await?;
+                return Ok(Some((resp, true)));
             }
             Ok(None) => {
                 debug!("readpicture command gave no result, falling back");
             }
             Err(e) => match e {
-                CommandError::ErrorResponse { error, .. } if error.code == 5 => {
+                CommandError::ErrorResponse { error, .. }
+                    if error.code == ErrorCode::UnknownCommand =>
+                {
                     debug!("readpicture command unsupported, falling back");
                 }
                 e => return Err(e),
             },
         }
 
-        if !embedded {
-            if let Some(resp) = self.command(cmds::AlbumArt::new(uri.to_owned())).await? {
-                expected_size = resp.size;
-                out.reserve(expected_size);
-                out.extend_from_slice(resp.data());
-                debug!(length = expected_size, "found separate file album art");
-            } else {
+        match self.command(cmds::AlbumArt::new(uri.to_owned())).await? {
+            Some(resp) => {
+                debug!(length = resp.size, "found separate file album art");
+                self.raise_binary_limit(&resp).await?;
+                Ok(Some((resp, false)))
+            }
+            None => {
                 debug!("no embedded or separate album art found");
-                return Ok(None);
+                Ok(None)
             }
         }
+    }
 
-        while out.len() < expected_size {
-            let resp = if embedded {
-                self.command(cmds::AlbumArtEmbedded::new(uri.to_owned()).offset(out.len()))
-                    .await?
-            } else {
-                self.command(cmds::AlbumArt::new(uri.to_owned()).offset(out.len()))
-                    .await?
-            };
+    /// Raise the binary limit for loading the remaining album art, if the first chunk indicates
+    /// that the current limit is lower and the server supports changing it.
+    async fn raise_binary_limit(
+        &self,
+        first: &cmds::responses::AlbumArt,
+    ) -> Result<(), CommandError> {
+        let chunk = first.data.len();
 
-            if let Some(resp) = resp {
-                let data = resp.data();
-                trace!(received = data.len(), progress = out.len());
-                out.extend_from_slice(data);
-            } else {
-                warn!(progress = out.len(), "incomplete cover art response");
-                return Ok(None);
+        if chunk >= first.size || chunk >= ALBUM_ART_BINARY_LIMIT {
+            return Ok(());
+        }
+
+        if !self.protocol_version_at_least(0, 22, 4) {
+            trace!("binarylimit command unsupported");
+            return Ok(());
+        }
+
+        debug!(
+            previous = chunk,
+            limit = ALBUM_ART_BINARY_LIMIT,
+            "raising binary limit"
+        );
+
+        match self
+            .command(cmds::SetBinaryLimit(ALBUM_ART_BINARY_LIMIT))
+            .await
+        {
+            Ok(()) => Ok(()),
+            Err(CommandError::ErrorResponse { error, .. }) => {
+                debug!(?error, "failed to raise binary limit");
+                Ok(())
             }
+            Err(e) => Err(e),
         }
+    }
 
-        debug!(length = expected_size, "finished loading");
+    /// Fail with [`CommandError::UnsupportedByServer`] if the protocol version is older than the
+    /// version required by a command.
+    fn check_protocol_version(
+        &self,
+        required: Option<(u32, u32, u32)>,
+    ) -> Result<(), CommandError> {
+        match required {
+            Some((major, minor, patch)) if !self.protocol_version_at_least(major, minor, patch) => {
+                debug!(?required, "command not supported by server");
+                Err(CommandError::UnsupportedByServer {
+                    required: (major, minor, patch),
+                })
+            }
+            _ => Ok(()),
+        }
+    }
 
-        Ok(Some((out, mime)))
+    /// Whether the protocol version is at least the given version.
+    fn protocol_version_at_least(&self, major: u32, minor: u32, patch: u32) -> bool {
+        let mut parts = self
+            .protocol_version
+            .split('.')
+            .map(|part| part.parse::<u32>().unwrap_or(0));
+
+        let version = (
+            parts.next().unwrap_or(0),
+            parts.next().unwrap_or(0),
+            parts.next().unwrap_or(0),
+        );
+
+        version >= (major, minor, patch)
+    }
+
+    /// Switch the connection into the partition with the given name.
+    ///
+    /// Unlike sending the [`SwitchPartition`](cmds::SwitchPartition) command directly, this
+    /// remembers the partition, so that it is restored if the client
+    /// [reconnects](Client::connect_with_reconnect).
+    ///
+    /// # Errors
+    ///
+    /// This returns errors in the same conditions as [`Client::command`].
+    pub async fn switch_partition(&self, name: String) -> Result<(), CommandError> {
+        self.command(cmds::SwitchPartition(name.clone())).await?;
+
+        let partition = if name == "default" { None } else { Some(name) };
+        *self.partition.lock().unwrap() = partition;
+
+        Ok(())
+    }
+
+    /// Wait for the given database update job to finish.
+    ///
+    /// This checks the [status](cmds::Status) of the server whenever an
+    /// [`Update`](Subsystem::Update) state change is received on `state_changes`, until the job is
+    /// neither running nor queued anymore.
+    ///
+    /// **Note**: Any other state changes received while waiting are discarded.
+    ///
+    /// # Errors
+    ///
+    /// This returns errors in the same conditions as [`Client::command`]. If the connection is
+    /// closed while waiting, [`CommandError::ConnectionClosed`] is returned.
+    pub async fn wait_for_update(
+        &self,
+        job: cmds::UpdateJob,
+        state_changes: &mut StateChanges,
+    ) -> Result<(), CommandError> {
+        loop {
+            let status = self.command(cmds::Status).await?;
+
+            // Jobs are processed in order, so a higher current ID means the job is finished
+            match status.update_job {
+                Some(current) if current <= job => trace!(?current, ?job, "update still running"),
+                _ => return Ok(()),
+            }
+
+            loop {
+                match state_changes.rx.recv().await {
+                    Some(Ok(Subsystem::Update)) | Some(Err(_)) => break,
+                    Some(Ok(_)) => continue,
+                    None => return Err(CommandError::ConnectionClosed),
+                }
+            }
+        }
+    }
+
+    /// Get a stream of the messages received on [subscribed](cmds::Subscribe) channels.
+    ///
+    /// Once this was called, the client automatically [reads the messages](cmds::ReadMessages)
+    /// whenever a [`Message`](Subsystem::Message) state change is received, as long as the returned
+    /// stream is alive. The state change is still emitted on the [`StateChanges`] stream.
+    ///
+    /// Only one stream can be active at a time, calling this again ends the previously returned
+    /// stream.
+    ///
+    /// **Note**: Subscriptions are not restored after [automatically
+    /// reconnecting](Client::connect_with_reconnect), and the returned stream ends when the
+    /// connection is lost.
+    pub fn messages(&self) -> Messages {
+        Messages::register(&self.messages)
+    }
+
+    /// Periodically ping the server while the connection is otherwise idle.
+    ///
+    /// When set, the client briefly leaves the idle state and sends a `ping` command whenever
+    /// neither a command was sent nor a state change was received within the given interval. This
+    /// keeps NAT and firewall state alive, and detects dead connections (which then trigger
+    /// [reconnecting](Client::connect_with_reconnect), if enabled). The connection is considered
+    /// dead if the server doesn't respond to the ping within the interval as well. Pass `None` to
+    /// disable it again, which is the default.
+    pub fn set_keepalive(&self, interval: Option<Duration>) {
+        let _ = self.keepalive.send(interval);
+    }
+
+    /// Set the default timeout for commands sent using this client and its clones.
+    ///
+    /// If the server doesn't respond to a command in time, [`CommandError::Timeout`] is returned.
+    /// Pass `None` to wait indefinitely, which is the default.
+    ///
+    /// **Note**: The timed out command stays in flight, and later commands are only sent after the
+    /// server responded to it.
+    pub fn set_command_timeout(&self, timeout: Option<Duration>) {
+        *self.command_timeout.lock().unwrap() = timeout;
+    }
+
+    /// Close the connection gracefully.
+    ///
+    /// New commands (including those sent through other clones of this client) are rejected with
+    /// [`CommandError::ConnectionClosed`], while commands that were already sent or queued are
+    /// still completed. Afterwards, the `close` command is sent to the server. The returned future
+    /// resolves once the connection task has exited.
+    pub async fn close(self) {
+        self.close.send_replace(true);
+
+        let mut events = self.connection_events.clone();
+        drop(self);
+
+        // The sender is dropped when the connection task exits
+        while events.changed().await.is_ok() {}
+    }
+
+    /// Get a receiver for the lifecycle events of the connection.
+    ///
+    /// The current value is the latest event, which can be used to display whether the client is
+    /// currently connected. Intermediate events may be missed if they happen in quick succession.
+    ///
+    /// Once the connection is closed for good, the last event is a
+    /// [`Disconnected`](ConnectionEvent::Disconnected) event and the receiver doesn't receive
+    /// further changes.
+    pub fn connection_events(&self) -> watch::Receiver<ConnectionEvent> {
+        self.connection_events.clone()
+    }
+
+    /// Install an observer which is notified about the activity on the connection, or remove the
+    /// current one by passing `None`.
+    ///
+    /// This can be used to collect metrics without depending on a particular metrics library.
+    /// Only activity after the observer was installed is reported.
+    pub fn set_observer(&self, observer: Option<Arc<dyn ConnectionObserver>>) {
+        self.observer.set(observer);
     }
 
     /// Get the protocol version the underlying connection is using.
@@ -300,25 +817,101 @@ impl Client {
     }
 
     async fn do_send(&self, commands: RawCommandList) -> Result<RawResponse, CommandError> {
-        let (tx, rx) = oneshot::channel();
+        let command_timeout = *self.command_timeout.lock().unwrap();
+        self.send_with_timeout(commands, command_timeout).await
+    }
+
+    async fn send_with_timeout(
+        &self,
+        commands: RawCommandList,
+        command_timeout: Option<Duration>,
+    ) -> Result<RawResponse, CommandError> {
+        let send = async {
+            let (tx, rx) = oneshot::channel();
 
-        self.commands_sender.send((commands, tx)).await?;
+            self.commands_sender.send((commands, tx)).await?;
+
+            rx.await?
+        };
 
-        rx.await?
+        match command_timeout {
+            Some(duration) => timeout(duration, send)
+                .await
+                .unwrap_or(Err(CommandError::Timeout)),
+            None => send.await,
+        }
     }
 }
 
-/// Perform the initial handshake to the server.
+/// Connect to the server and spawn the run loop.
 async fn do_connect<IO: AsyncRead + AsyncWrite + Unpin + Send + 'static>(
     io: IO,
     password: Option<&str>,
+    reconnect: Option<Reconnect<IO>>,
 ) -> Result<Connection, ConnectWithPasswordError> {
     let span = span!(Level::DEBUG, "client connection");
 
     let (state_changes_sender, state_changes) = mpsc::unbounded_channel();
     let (commands_sender, commands_receiver) = mpsc::channel(1);
 
-    let mut connection = match AsyncConnection::connect(io).instrument(span.clone()).await {
+    let observer = match &reconnect {
+        Some(reconnect) => reconnect.observer.clone(),
+        None => ObserverSlot::default(),
+    };
+    let io = Metered::new(io, observer.clone());
+
+    let connection = handshake(io, password).instrument(span.clone()).await?;
+    let protocol_version = Arc::from(connection.protocol_version());
+    let partition = match &reconnect {
+        Some(reconnect) => Arc::clone(&reconnect.partition),
+        None => Arc::default(),
+    };
+    let messages = MessagesSender::default();
+    let (keepalive, keepalive_receiver) = watch::channel(None);
+    let (close, close_receiver) = watch::channel(false);
+    let (events, connection_events) = watch::channel(ConnectionEvent::Connected {
+        protocol_version: Arc::clone(&protocol_version),
+    });
+
+    tokio::spawn(
+        connection::run_loop(
+            connection,
+            connection::Channels {
+                commands: commands_receiver,
+                state_changes: state_changes_sender,
+                messages: Arc::clone(&messages),
+                keepalive: keepalive_receiver,
+                observer: observer.clone(),
+                events,
+                close: close_receiver,
+            },
+            reconnect,
+        )
+        .instrument(span!(parent: &span, Level::TRACE, "run loop")),
+    );
+
+    let state_changes = StateChanges { rx: state_changes };
+    let client = Client {
+        commands_sender,
+        protocol_version,
+        partition,
+        messages,
+        keepalive: Arc::new(keepalive),
+        command_timeout: Arc::default(),
+        observer,
+        connection_events,
+        close: Arc::new(close),
+    };
+
+    Ok((client, state_changes))
+}
+
+/// Perform the initial handshake to the server, including sending the password if necessary.
+async fn handshake<IO: AsyncRead + AsyncWrite + Unpin>(
+    io: IO,
+    password: Option<&str>,
+) -> Result<AsyncConnection<IO>, ConnectWithPasswordError> {
+    let mut connection = match AsyncConnection::connect(io).await {
         Ok(c) => c,
         Err(e) => {
             error!(error = ?e, "failed to perform initial handshake");
@@ -326,30 +919,24 @@ async fn do_connect<IO: AsyncRead + AsyncWrite + Unpin + Send + 'static>(
         }
     };
 
-    let protocol_version = Arc::from(connection.protocol_version());
-
     if let Some(password) = password {
-        trace!(parent: &span, "sending password");
+        trace!("sending password");
 
         if let Err(e) = connection
             .send(RawCommand::new("password").argument(password.to_owned()))
-            .instrument(span.clone())
             .await
         {
-            error!(parent: &span, error = ?e, "failed to send password");
+            error!(error = ?e, "failed to send password");
             return Err(e.into());
         }
 
-        match connection.receive().instrument(span.clone()).await {
+        match connection.receive().await {
             Err(e) => {
-                error!(parent: &span, error = ?e, "failed to receive reply to password");
+                error!(error = ?e, "failed to receive reply to password");
                 return Err(e.into());
             }
             Ok(None) => {
-                error!(
-                    parent: &span,
-                    "unexpected end of stream after sending password"
-                );
+                error!("unexpected end of stream after sending password");
                 return Err(MpdProtocolError::Io(io::Error::new(
                     io::ErrorKind::UnexpectedEof,
                     "connection closed while waiting for reply to password",
@@ -357,28 +944,17 @@ async fn do_connect<IO: AsyncRead + AsyncWrite + Unpin + Send + 'static>(
                 .into());
             }
             Ok(Some(response)) if response.is_error() => {
-                error!(parent: &span, "incorrect password");
+                error!("incorrect password");
                 return Err(ConnectWithPasswordError::IncorrectPassword);
             }
             Ok(Some(_)) => {
-                trace!(parent: &span, "password accepted");
+                trace!("password accepted");
             }
         }
     }
 
-    tokio::spawn(
-        connection::run_loop(connection, commands_receiver, state_changes_sender)
-            .instrument(span!(parent: &span, Level::TRACE, "run loop")),
-    );
-
-    let state_changes = StateChanges { rx: state_changes };
-    let client = Client {
-        commands_sender,
-        protocol_version,
-    };
-
-    Ok((client, state_changes))
-}
+    Ok(connection)
+}
 
 /// Error returned when [connecting with a password][Client::connect_with_password] fails.
 #[derive(Debug)]
@@ -417,8 +993,8 @@ impl From<MpdProtocolError> for ConnectWithPasswordError {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::state_changes::Subsystem;
     use futures_util::StreamExt;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
     use tokio_test::{assert_ok, io::Builder as MockBuilder};
 
     static GREETING: &[u8] = b"OK MPD 0.21.11\n";
@@ -440,6 +1016,248 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn connect_tcp() {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap();
+
+        tokio::spawn(async move {
+            let (mut stream, _) = listener.accept().await.unwrap();
+            stream.write_all(GREETING).await.unwrap();
+            stream.read_to_end(&mut Vec::new()).await.unwrap();
+        });
+
+        let (client, _) = Client::connect_tcp(address).await.expect("connect failed");
+        assert_eq!(client.protocol_version(), "0.21.11");
+    }
+
+    #[tokio::test]
+    async fn reconnect() {
+        let first = MockBuilder::new().read(GREETING).write(b"idle\n").build();
+        let second = MockBuilder::new()
+            .read(GREETING)
+            .write(b"idle\n")
+            .write(b"noidle\n")
+            .read(b"OK\n")
+            .write(b"ping\n")
+            .read(b"OK\n")
+            .write(b"idle\n")
+            .build();
+
+        let (reconnected_tx, reconnected_rx) = oneshot::channel();
+        let mut connections = vec![second, first];
+        let mut reconnected_tx = Some(reconnected_tx);
+
+        let connect = move || {
+            let io = connections.pop().expect("too many connection attempts");
+
+            if connections.is_empty() {
+                let _ = reconnected_tx.take().unwrap().send(());
+            }
+
+            Box::pin(async move { Ok(io) }) as Pin<Box<dyn Future<Output = _> + Send>>
+        };
+
+        let (client, _state_changes) =
+            Client::connect_with_reconnect(connect, None, ReconnectOptions::new())
+                .await
+                .expect("connect failed");
+
+        reconnected_rx.await.unwrap();
+        assert_ok!(client.command(cmds::Ping).await);
+    }
+
+    #[tokio::test]
+    async fn reconnecting_commands() {
+        let first = MockBuilder::new()
+            .read(GREETING)
+            .write(b"idle\n")
+            .write(b"noidle\n")
+            .read(b"OK\n")
+            .write(b"ping\n")
+            .read_error(io::Error::from(io::ErrorKind::ConnectionReset))
+            .build();
+
+        let mut first = Some(first);
+        let connect = move || match first.take() {
+            Some(io) => Box::pin(async move { Ok(io) }) as Pin<Box<dyn Future<Output = _> + Send>>,
+            // Never finish connecting
+            None => Box::pin(std::future::pending()),
+        };
+
+        let (client, _state_changes) =
+            Client::connect_with_reconnect(connect, None, ReconnectOptions::new())
+                .await
+                .expect("connect failed");
+
+        // The connection is lost while waiting for the response
+        assert!(matches!(
+            client.command(cmds::Ping).await,
+            Err(CommandError::Reconnecting)
+        ));
+
+        // The reconnection attempt is in progress
+        assert!(matches!(
+            client.command(cmds::Ping).await,
+            Err(CommandError::Reconnecting)
+        ));
+    }
+
+    #[tokio::test]
+    async fn connection_events() {
+        let mut connections = vec![MockBuilder::new().read(GREETING).write(b"idle\n").build()];
+
+        let connect = move || {
+            let io = connections
+                .pop()
+                .ok_or_else(|| io::Error::from(io::ErrorKind::NotFound));
+            Box::pin(async move { io }) as Pin<Box<dyn Future<Output = _> + Send>>
+        };
+
+        let options = ReconnectOptions::new().max_attempts(1);
+        let (client, _state_changes) = Client::connect_with_reconnect(connect, None, options)
+            .await
+            .expect("connect failed");
+
+        let mut events = client.connection_events();
+        assert_eq!(
+            *events.borrow(),
+            ConnectionEvent::Connected {
+                protocol_version: Arc::from("0.21.11")
+            }
+        );
+
+        // The run loop exits after giving up, dropping the sender
+        while events.changed().await.is_ok() {}
+
+        assert_eq!(
+            *events.borrow(),
+            ConnectionEvent::Disconnected {
+                reason: DisconnectReason::ReconnectFailed
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn close() {
+        let io = MockBuilder::new()
+            .read(GREETING)
+            .write(b"idle\n")
+            .write(b"noidle\n")
+            .read(b"OK\n")
+            .write(b"ping\n")
+            .read(b"OK\n")
+            .write(b"idle\n")
+            .write(b"noidle\n")
+            .read(b"OK\n")
+            .write(b"close\n")
+            .build();
+
+        let (client, _state_changes) = Client::connect(io).await.expect("connect failed");
+        let other = client.clone();
+        let events = client.connection_events();
+
+        assert_ok!(client.command(cmds::Ping).await);
+        client.close().await;
+
+        assert!(matches!(
+            other.command(cmds::Ping).await,
+            Err(CommandError::ConnectionClosed)
+        ));
+        assert_eq!(
+            *events.borrow(),
+            ConnectionEvent::Disconnected {
+                reason: DisconnectReason::Closed
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn close_while_reconnecting() {
+        let mut first = Some(MockBuilder::new().read(GREETING).write(b"idle\n").build());
+        let connect = move || {
+            let io = first
+                .take()
+                .ok_or_else(|| io::Error::from(io::ErrorKind::ConnectionRefused));
+            Box::pin(async move { io }) as Pin<Box<dyn Future<Output = _> + Send>>
+        };
+
+        let options = ReconnectOptions::new().initial_delay(Duration::from_millis(10));
+        let (client, _state_changes) = Client::connect_with_reconnect(connect, None, options)
+            .await
+            .expect("connect failed");
+        let other = client.clone();
+        let mut events = other.connection_events();
+
+        // Wait until the first reconnection attempt has failed
+        while !matches!(
+            *events.borrow_and_update(),
+            ConnectionEvent::Reconnecting { attempt: 2 }
+        ) {
+            events.changed().await.unwrap();
+        }
+
+        client.close().await;
+
+        assert!(matches!(
+            other.command(cmds::Ping).await,
+            Err(CommandError::ConnectionClosed)
+        ));
+        assert_eq!(
+            *events.borrow(),
+            ConnectionEvent::Disconnected {
+                reason: DisconnectReason::Closed
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn reconnect_restores_partition() {
+        let first = MockBuilder::new()
+            .read(GREETING)
+            .write(b"idle\n")
+            .write(b"noidle\n")
+            .read(b"OK\n")
+            .write(b"partition foo\n")
+            .read(b"OK\n")
+            .write(b"idle\n")
+            .build();
+        let second = MockBuilder::new()
+            .read(GREETING)
+            .write(b"partition foo\n")
+            .read(b"OK\n")
+            .write(b"idle\n")
+            .write(b"noidle\n")
+            .read(b"OK\n")
+            .write(b"ping\n")
+            .read(b"OK\n")
+            .write(b"idle\n")
+            .build();
+
+        let (reconnected_tx, reconnected_rx) = oneshot::channel();
+        let mut connections = vec![second, first];
+        let mut reconnected_tx = Some(reconnected_tx);
+
+        let connect = move || {
+            let io = connections.pop().expect("too many connection attempts");
+
+            if connections.is_empty() {
+                let _ = reconnected_tx.take().unwrap().send(());
+            }
+
+            Box::pin(async move { Ok(io) }) as Pin<Box<dyn Future<Output = _> + Send>>
+        };
+
+        let (client, _state_changes) =
+            Client::connect_with_reconnect(connect, None, ReconnectOptions::new())
+                .await
+                .expect("connect failed");
+
+        assert_ok!(client.switch_partition(String::from("foo")).await);
+        reconnected_rx.await.unwrap();
+        assert_ok!(client.command(cmds::Ping).await);
+    }
+
     #[tokio::test]
     async fn command() {
         let io = MockBuilder::new()
@@ -467,6 +1285,391 @@ mod tests {
         assert!(state_changes.next().await.is_none());
     }
 
+    #[tokio::test]
+    async fn wait_for_update() {
+        let io = MockBuilder::new()
+            .read(GREETING)
+            .write(b"idle\n")
+            .write(b"noidle\n")
+            .read(b"OK\n")
+            .write(b"status\n")
+            .read(b"repeat: 0\nrandom: 0\nconsume: 0\nstate: stop\nupdate_job: 3\nOK\n")
+            .write(b"idle\n")
+            .read(b"changed: player\nOK\n")
+            .write(b"idle\n")
+            .read(b"changed: update\nOK\n")
+            .write(b"idle\n")
+            .write(b"noidle\n")
+            .read(b"OK\n")
+            .write(b"status\n")
+            .read(b"repeat: 0\nrandom: 0\nconsume: 0\nstate: stop\nOK\n")
+            .write(b"idle\n")
+            .build();
+
+        let (client, mut state_changes) = Client::connect(io).await.expect("connect failed");
+
+        assert_ok!(
+            client
+                .wait_for_update(cmds::UpdateJob(3), &mut state_changes)
+                .await
+        );
+    }
+
+    #[tokio::test]
+    async fn messages() {
+        let io = MockBuilder::new()
+            .read(GREETING)
+            .write(b"idle\n")
+            .write(b"noidle\n")
+            .read(b"OK\n")
+            .write(b"subscribe foo\n")
+            .read(b"OK\n")
+            .write(b"idle\n")
+            .read(b"changed: message\nOK\n")
+            .write(b"readmessages\n")
+            .read(b"channel: foo\nmessage: hello\nchannel: foo\nmessage: world\nOK\n")
+            .write(b"idle\n")
+            .build();
+
+        let (client, mut state_changes) = Client::connect(io).await.expect("connect failed");
+        let mut messages = client.messages();
+
+        assert_ok!(client.command(cmds::Subscribe(String::from("foo"))).await);
+
+        assert_eq!(
+            assert_ok!(state_changes.next().await.expect("no state change")),
+            Subsystem::Message
+        );
+
+        let message = messages.next().await.expect("no message");
+        assert_eq!(message.channel, "foo");
+        assert_eq!(message.message, "hello");
+
+        let message = messages.next().await.expect("no message");
+        assert_eq!(message.message, "world");
+    }
+
+    #[tokio::test]
+    async fn messages_before_command() {
+        let io = MockBuilder::new()
+            .read(GREETING)
+            .write(b"idle\n")
+            .write(b"noidle\n")
+            .read(b"changed: message\nOK\n")
+            .write(b"readmessages\n")
+            .read(b"channel: foo\nmessage: hello\nOK\n")
+            .write(b"ping\n")
+            .read(b"OK\n")
+            .write(b"idle\n")
+            .build();
+
+        let (client, mut state_changes) = Client::connect(io).await.expect("connect failed");
+        let mut messages = client.messages();
+
+        assert_ok!(client.command(cmds::Ping).await);
+
+        assert_eq!(
+            assert_ok!(state_changes.next().await.expect("no state change")),
+            Subsystem::Message
+        );
+
+        let message = messages.next().await.expect("no message");
+        assert_eq!(message.message, "hello");
+
+        // The connection is closed by the server, which ends the stream
+        assert!(messages.next().await.is_none());
+    }
+
+    #[tokio::test]
+    async fn keepalive() {
+        let io = MockBuilder::new()
+            .read(GREETING)
+            .write(b"idle\n")
+            .write(b"noidle\n")
+            .read(b"OK\n")
+            .write(b"ping\n")
+            .read(b"OK\n")
+            .write(b"idle\n")
+            .read(b"changed: player\nOK\n")
+            .write(b"idle\n")
+            .build();
+
+        let (client, mut state_changes) = Client::connect(io).await.expect("connect failed");
+        client.set_keepalive(Some(Duration::from_millis(10)));
+
+        let state_change = timeout(Duration::from_secs(1), state_changes.next())
+            .await
+            .expect("no state change after keepalive");
+        assert_eq!(
+            assert_ok!(state_change.expect("connection closed")),
+            Subsystem::Player
+        );
+
+        client.set_keepalive(None);
+    }
+
+    #[tokio::test]
+    async fn keepalive_timeout() {
+        let io = MockBuilder::new()
+            .read(GREETING)
+            .write(b"idle\n")
+            .write(b"noidle\n")
+            // The server stops responding
+            .wait(Duration::from_secs(60))
+            .build();
+
+        let (client, mut state_changes) = Client::connect(io).await.expect("connect failed");
+        let events = client.connection_events();
+        client.set_keepalive(Some(Duration::from_millis(10)));
+
+        let state_change = timeout(Duration::from_secs(1), state_changes.next())
+            .await
+            .expect("connection not lost after keepalive timeout");
+        assert!(state_change.is_none());
+        assert_eq!(
+            *events.borrow(),
+            ConnectionEvent::Disconnected {
+                reason: DisconnectReason::ConnectionLost
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn album_art_stream() {
+        let io = MockBuilder::new()
+            .read(GREETING)
+            .write(b"idle\n")
+            .write(b"noidle\n")
+            .read(b"OK\n")
+            .write(b"readpicture foo 0\n")
+            .read(b"size: 6\ntype: image/png\nbinary: 3\nabc\nOK\n")
+            .write(b"readpicture foo 3\n")
+            .read(b"size: 6\ntype: image/png\nbinary: 3\ndef\nOK\n")
+            .write(b"idle\n")
+            .build();
+
+        let (client, _state_changes) = Client::connect(io).await.expect("connect failed");
+
+        let mut stream = assert_ok!(client.album_art_stream("foo").await).expect("no album art");
+        assert_eq!(stream.size(), 6);
+        assert_eq!(stream.mime(), Some("image/png"));
+
+        assert_eq!(assert_ok!(stream.next().await.unwrap()), &b"abc"[..]);
+        assert_eq!(assert_ok!(stream.next().await.unwrap()), &b"def"[..]);
+        assert!(stream.next().await.is_none());
+    }
+
+    #[tokio::test]
+    async fn album_art_raises_binary_limit() {
+        let io = MockBuilder::new()
+            .read(b"OK MPD 0.23.5\n")
+            .write(b"idle\n")
+            .write(b"noidle\n")
+            .read(b"OK\n")
+            .write(b"readpicture foo 0\n")
+            .read(b"size: 6\nbinary: 3\nabc\nOK\n")
+            .write(b"binarylimit 1048576\n")
+            .read(b"OK\n")
+            .write(b"readpicture foo 3\n")
+            .read(b"size: 6\nbinary: 3\ndef\nOK\n")
+            .write(b"idle\n")
+            .build();
+
+        let (client, _state_changes) = Client::connect(io).await.expect("connect failed");
+
+        let (data, _) = assert_ok!(client.album_art("foo").await).expect("no album art");
+        assert_eq!(data, b"abcdef");
+    }
+
+    #[tokio::test]
+    async fn command_timeout() {
+        let io = MockBuilder::new()
+            .read(GREETING)
+            .write(b"idle\n")
+            .write(b"noidle\n")
+            .read(b"OK\n")
+            .write(b"ping\n")
+            .wait(Duration::from_millis(100))
+            .read(b"OK\n")
+            .write(b"ping\n")
+            .read(b"OK\n")
+            .write(b"idle\n")
+            .build();
+
+        let (client, _state_changes) = Client::connect(io).await.expect("connect failed");
+
+        assert!(matches!(
+            client
+                .command_with_timeout(cmds::Ping, Duration::from_millis(10))
+                .await,
+            Err(CommandError::Timeout)
+        ));
+
+        client.set_command_timeout(Some(Duration::from_secs(1)));
+        assert_ok!(client.command(cmds::Ping).await);
+    }
+
+    #[tokio::test]
+    async fn dropped_command() {
+        let io = MockBuilder::new()
+            .read(b"OK MPD 0.23.5\n")
+            .write(b"idle\n")
+            .write(b"noidle\n")
+            .read(b"OK\n")
+            .write(b"status\n")
+            .wait(Duration::from_millis(50))
+            .read(b"repeat: 0\nrandom: 0\nconsume: 0\nstate: stop\nOK\n")
+            .write(b"getvol\n")
+            .read(b"volume: 50\nOK\n")
+            .write(b"idle\n")
+            .build();
+
+        let (client, _state_changes) = Client::connect(io).await.expect("connect failed");
+
+        // Drop the command future after the command was sent, but before the response arrived
+        let status = timeout(Duration::from_millis(10), client.command(cmds::Status)).await;
+        assert!(status.is_err());
+
+        assert_eq!(
+            assert_ok!(client.command(cmds::GetVolume).await),
+            crate::commands::responses::Volume(Some(50))
+        );
+    }
+
+    #[tokio::test]
+    async fn observer() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        #[derive(Default)]
+        struct Counts {
+            commands: AtomicUsize,
+            failures: AtomicUsize,
+            sent: AtomicUsize,
+            received: AtomicUsize,
+        }
+
+        impl ConnectionObserver for Counts {
+            fn command_sent(&self, _: &RawCommandList) {
+                self.commands.fetch_add(1, Ordering::SeqCst);
+            }
+
+            fn response_received(&self, _: Duration, success: bool) {
+                if !success {
+                    self.failures.fetch_add(1, Ordering::SeqCst);
+                }
+            }
+
+            fn bytes_sent(&self, count: usize) {
+                self.sent.fetch_add(count, Ordering::SeqCst);
+            }
+
+            fn bytes_received(&self, count: usize) {
+                self.received.fetch_add(count, Ordering::SeqCst);
+            }
+        }
+
+        let io = MockBuilder::new()
+            .read(GREETING)
+            .write(b"idle\n")
+            .write(b"noidle\n")
+            .read(b"OK\n")
+            .write(b"ping\n")
+            .read(b"OK\n")
+            .write(b"hello\n")
+            .read(b"ACK [5@0] {} unknown command\n")
+            .write(b"idle\n")
+            .build();
+
+        let (client, _state_changes) = Client::connect(io).await.expect("connect failed");
+
+        let counts = Arc::new(Counts::default());
+        client.set_observer(Some(counts.clone()));
+
+        assert_ok!(client.command(cmds::Ping).await);
+        assert!(client.raw_command(RawCommand::new("hello")).await.is_err());
+
+        assert_eq!(counts.commands.load(Ordering::SeqCst), 2);
+        assert_eq!(counts.failures.load(Ordering::SeqCst), 1);
+        // The initial `idle` may or may not have been sent before the observer was installed
+        assert!(counts.sent.load(Ordering::SeqCst) >= 18);
+        assert_eq!(counts.received.load(Ordering::SeqCst), 35);
+    }
+
+    #[tokio::test]
+    async fn unsupported_by_server() {
+        let io = MockBuilder::new().read(GREETING).write(b"idle\n").build();
+
+        let (client, _state_changes) = Client::connect(io).await.expect("connect failed");
+
+        assert!(matches!(
+            client.command(cmds::GetVolume).await,
+            Err(CommandError::UnsupportedByServer {
+                required: (0, 23, 0)
+            })
+        ));
+        assert!(matches!(
+            client.command_list((cmds::Ping, cmds::GetVolume)).await,
+            Err(CommandError::UnsupportedByServer {
+                required: (0, 23, 0)
+            })
+        ));
+    }
+
+    #[tokio::test]
+    async fn closed_by_server() {
+        let io = MockBuilder::new()
+            .read(GREETING)
+            .write(b"idle\n")
+            .write(b"noidle\n")
+            .read(b"OK\n")
+            .write(b"kill\n")
+            .build();
+
+        let (client, _state_changes) = Client::connect(io).await.expect("connect failed");
+
+        assert!(matches!(
+            client.command(cmds::Kill).await,
+            Err(CommandError::ClosedByServer)
+        ));
+        assert!(matches!(
+            client.command(cmds::Ping).await,
+            Err(CommandError::ConnectionClosed)
+        ));
+    }
+
+    #[tokio::test]
+    async fn closed_by_server_with_reconnect() {
+        let first = MockBuilder::new()
+            .read(GREETING)
+            .write(b"idle\n")
+            .write(b"noidle\n")
+            .read(b"OK\n")
+            .write(b"kill\n")
+            .build();
+
+        let mut first = Some(first);
+        let connect = move || match first.take() {
+            Some(io) => Box::pin(async move { Ok(io) }) as Pin<Box<dyn Future<Output = _> + Send>>,
+            // Never finish connecting
+            None => Box::pin(std::future::pending()),
+        };
+
+        let (client, _state_changes) =
+            Client::connect_with_reconnect(connect, None, ReconnectOptions::new())
+                .await
+                .expect("connect failed");
+
+        // The command was executed, so it isn't reported as lost
+        assert!(matches!(
+            client.command(cmds::Kill).await,
+            Err(CommandError::ClosedByServer)
+        ));
+        assert!(matches!(
+            client.command(cmds::Ping).await,
+            Err(CommandError::Reconnecting)
+        ));
+    }
+
     #[tokio::test]
     async fn incomplete_response() {
         let io = MockBuilder::new()
@@ -517,6 +1720,29 @@ mod tests {
         assert_eq!(responses[0].find("foo"), Some("asdf"));
     }
 
+    #[tokio::test]
+    async fn typed_command_list() {
+        let io = MockBuilder::new()
+            .read(b"OK MPD 0.23.5\n")
+            .write(b"idle\n")
+            .write(b"noidle\n")
+            .read(b"OK\n")
+            .write(b"command_list_ok_begin\nping\ngetvol\nchannels\nping\ncommand_list_end\n")
+            .read(b"list_OK\nvolume: 50\nlist_OK\nchannel: foo\nlist_OK\nlist_OK\nOK\n")
+            .write(b"idle\n")
+            .build();
+
+        let (client, _state_changes) = Client::connect(io).await.expect("connect failed");
+
+        let (_, volume, channels, _) = client
+            .command_list((cmds::Ping, cmds::GetVolume, cmds::Channels, cmds::Ping))
+            .await
+            .expect("command failed");
+
+        assert_eq!(volume, crate::commands::responses::Volume(Some(50)));
+        assert_eq!(channels, vec![String::from("foo")]);
+    }
+
     #[tokio::test]
     async fn dropping_client() {
         let io = MockBuilder::new().read(GREETING).write(b"idle\n").build();
@@ -0,0 +1,126 @@
+use tokio::io::{AsyncRead, AsyncWrite, ReadBuf};
+
+use std::fmt;
+use std::io;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll};
+use std::time::Duration;
+
+use crate::raw::RawCommandList;
+
+/// Hooks for observing the activity on a connection, e.g. for collecting metrics.
+///
+/// All methods have empty default implementations, so implementations only need to override the
+/// events they are interested in. The methods are called from the task driving the connection,
+/// and should therefore return quickly.
+///
+/// Install an observer using [`Client::set_observer`](crate::Client::set_observer).
+pub trait ConnectionObserver: Send + Sync {
+    /// A command (list) issued through the client was sent to the server.
+    ///
+    /// This is not called for the commands the client sends internally, such as `idle`.
+    fn command_sent(&self, _commands: &RawCommandList) {}
+
+    /// The response to a command (list) was received after the given time.
+    ///
+    /// `success` is `false` if the server responded with an error or the response could not be
+    /// read.
+    fn response_received(&self, _latency: Duration, _success: bool) {}
+
+    /// The given number of bytes were written to the connection.
+    fn bytes_sent(&self, _count: usize) {}
+
+    /// The given number of bytes were read from the connection.
+    fn bytes_received(&self, _count: usize) {}
+
+    /// The connection was reestablished after the given number of attempts.
+    ///
+    /// This is only called for clients created using
+    /// [`Client::connect_with_reconnect`](crate::Client::connect_with_reconnect).
+    fn reconnected(&self, _attempts: u32) {}
+}
+
+/// The observer installed on a client, shared with the connection task.
+#[derive(Clone, Default)]
+pub(super) struct ObserverSlot(Arc<Mutex<Option<Arc<dyn ConnectionObserver>>>>);
+
+impl ObserverSlot {
+    pub(super) fn set(&self, observer: Option<Arc<dyn ConnectionObserver>>) {
+        *self.0.lock().unwrap() = observer;
+    }
+
+    /// Call the given function with the observer, if one is installed.
+    pub(super) fn notify(&self, f: impl FnOnce(&dyn ConnectionObserver)) {
+        // Clone the observer to avoid holding the lock while calling into user code
+        let observer = self.0.lock().unwrap().clone();
+
+        if let Some(observer) = observer {
+            f(&*observer);
+        }
+    }
+}
+
+impl fmt::Debug for ObserverSlot {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let installed = self.0.lock().unwrap().is_some();
+        f.debug_tuple("ObserverSlot").field(&installed).finish()
+    }
+}
+
+/// Transport wrapper reporting the number of bytes read and written to the observer.
+#[derive(Debug)]
+pub(super) struct Metered<IO> {
+    io: IO,
+    observer: ObserverSlot,
+}
+
+impl<IO> Metered<IO> {
+    pub(super) fn new(io: IO, observer: ObserverSlot) -> Self {
+        Self { io, observer }
+    }
+}
+
+impl<IO: AsyncRead + Unpin> AsyncRead for Metered<IO> {
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        let before = buf.filled().len();
+        let res = Pin::new(&mut self.io).poll_read(cx, buf);
+
+        let count = buf.filled().len() - before;
+        if count > 0 {
+            self.observer.notify(|o| o.bytes_received(count));
+        }
+
+        res
+    }
+}
+
+impl<IO: AsyncWrite + Unpin> AsyncWrite for Metered<IO> {
+    fn poll_write(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        let res = Pin::new(&mut self.io).poll_write(cx, buf);
+
+        if let Poll::Ready(Ok(count)) = res {
+            if count > 0 {
+                self.observer.notify(|o| o.bytes_sent(count));
+            }
+        }
+
+        res
+    }
+
+    fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.io).poll_flush(cx)
+    }
+
+    fn poll_shutdown(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.io).poll_shutdown(cx)
+    }
+}
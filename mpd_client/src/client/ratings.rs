@@ -0,0 +1,180 @@
+use std::borrow::Cow;
+use std::error::Error;
+use std::fmt;
+use std::ops::RangeInclusive;
+
+use super::Client;
+use crate::commands::{responses::TypedResponseError, StickerGet, StickerSet, StickerType};
+use crate::errors::CommandError;
+use crate::raw::ErrorCode;
+
+/// Sticker name used by [`Ratings::new`].
+const DEFAULT_STICKER: &str = "rating";
+
+/// Range of valid ratings.
+const VALID_RATINGS: RangeInclusive<u8> = 1..=10;
+
+/// A song rating, in the range `1..=10`.
+///
+/// Create one using its [`TryFrom<u8>`] implementation.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Rating(u8);
+
+impl Rating {
+    /// Get the value of the rating.
+    pub fn get(self) -> u8 {
+        self.0
+    }
+}
+
+impl TryFrom<u8> for Rating {
+    type Error = RatingError;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        if VALID_RATINGS.contains(&value) {
+            Ok(Rating(value))
+        } else {
+            Err(RatingError(value))
+        }
+    }
+}
+
+impl From<Rating> for u8 {
+    fn from(rating: Rating) -> Self {
+        rating.0
+    }
+}
+
+/// Error returned when creating a [`Rating`] from a value outside of the range `1..=10`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RatingError(pub u8);
+
+impl fmt::Display for RatingError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "rating {} is not in the range 1..=10", self.0)
+    }
+}
+
+impl Error for RatingError {}
+
+/// Song ratings stored in [stickers][StickerGet].
+///
+/// Ratings are integers in the range `1..=10`, stored as the value of a sticker on the song.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Ratings {
+    sticker: Cow<'static, str>,
+}
+
+impl Ratings {
+    /// Store ratings in the sticker named `rating`.
+    pub fn new() -> Self {
+        Self {
+            sticker: Cow::Borrowed(DEFAULT_STICKER),
+        }
+    }
+
+    /// Store ratings in the sticker with the given name.
+    pub fn sticker_name(mut self, name: String) -> Self {
+        self.sticker = Cow::Owned(name);
+        self
+    }
+
+    /// Get the rating of the song with the given URI, or `None` if the song is not rated.
+    ///
+    /// # Errors
+    ///
+    /// This returns errors in the same conditions as [`Client::command`]. If the stored value is
+    /// not a valid rating, [`CommandError::InvalidTypedResponse`] is returned.
+    pub async fn get(&self, client: &Client, uri: &str) -> Result<Option<Rating>, CommandError> {
+        let command = StickerGet::new(
+            StickerType::Song,
+            uri.to_owned(),
+            self.sticker.clone().into_owned(),
+        );
+
+        let sticker = match client.command(command).await {
+            Ok(sticker) => sticker,
+            Err(CommandError::ErrorResponse { error, .. }) if error.code == ErrorCode::NoExist => {
+                return Ok(None)
+            }
+            Err(e) => return Err(e),
+        };
+
+        match sticker.value.parse::<u8>().map(Rating::try_from) {
+            Ok(Ok(rating)) => Ok(Some(rating)),
+            _ => Err(CommandError::InvalidTypedResponse(
+                TypedResponseError::invalid_value("sticker", sticker.value),
+            )),
+        }
+    }
+
+    /// Set the rating of the song with the given URI.
+    ///
+    /// # Errors
+    ///
+    /// This returns errors in the same conditions as [`Client::command`].
+    pub async fn set(
+        &self,
+        client: &Client,
+        uri: &str,
+        rating: Rating,
+    ) -> Result<(), CommandError> {
+        let command = StickerSet::new(
+            StickerType::Song,
+            uri.to_owned(),
+            self.sticker.clone().into_owned(),
+            rating.0.to_string(),
+        );
+
+        client.command(command).await
+    }
+}
+
+impl Default for Ratings {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio_test::{assert_ok, io::Builder as MockBuilder};
+
+    static GREETING: &[u8] = b"OK MPD 0.23.5\n";
+
+    #[tokio::test]
+    async fn ratings() {
+        let io = MockBuilder::new()
+            .read(GREETING)
+            .write(b"idle\n")
+            .write(b"noidle\n")
+            .read(b"OK\n")
+            .write(b"sticker get song foo.flac stars\n")
+            .read(b"sticker: stars=7\nOK\n")
+            .write(b"sticker get song bar.flac stars\n")
+            .read(b"ACK [50@0] {sticker} no such sticker\n")
+            .write(b"sticker set song bar.flac stars 10\n")
+            .read(b"OK\n")
+            .write(b"idle\n")
+            .build();
+
+        let (client, _state_changes) = Client::connect(io).await.expect("connect failed");
+        let ratings = Ratings::new().sticker_name(String::from("stars"));
+
+        assert_eq!(
+            assert_ok!(ratings.get(&client, "foo.flac").await),
+            Some(Rating(7))
+        );
+        assert_eq!(assert_ok!(ratings.get(&client, "bar.flac").await), None);
+        assert_ok!(ratings.set(&client, "bar.flac", Rating(10)).await);
+    }
+
+    #[test]
+    fn rating_range() {
+        assert_eq!(Rating::try_from(0), Err(RatingError(0)));
+        assert_eq!(Rating::try_from(1).map(Rating::get), Ok(1));
+        assert_eq!(Rating::try_from(10).map(Rating::get), Ok(10));
+        assert_eq!(Rating::try_from(11), Err(RatingError(11)));
+    }
+}
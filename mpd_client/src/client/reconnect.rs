@@ -0,0 +1,204 @@
+use mpd_protocol::AsyncConnection;
+use tokio::{
+    io::{AsyncRead, AsyncWrite},
+    sync::{mpsc::Receiver, watch},
+    time::sleep,
+};
+use tracing::{debug, error, warn};
+
+use std::cmp::min;
+use std::future::Future;
+use std::io;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use super::{
+    handshake,
+    lifecycle::{ConnectionEvent, DisconnectReason, EventsSender},
+    observer::{Metered, ObserverSlot},
+    CommandResponder, ConnectWithPasswordError,
+};
+use crate::{
+    errors::CommandError,
+    raw::{MpdProtocolError, RawCommand, RawCommandList},
+};
+
+type ConnectFuture<C> = Pin<Box<dyn Future<Output = io::Result<C>> + Send>>;
+
+/// Function establishing a new connection.
+pub(super) type Connector<C> = Box<dyn FnMut() -> ConnectFuture<C> + Send>;
+
+/// Options for [automatically reconnecting][crate::Client::connect_with_reconnect] after the
+/// connection was lost.
+///
+/// After the connection is lost, a new connection is attempted immediately. If that fails, further
+/// attempts are made after a delay, which starts at the initial delay and doubles after every
+/// failed attempt up to the maximum delay.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ReconnectOptions {
+    initial_delay: Duration,
+    max_delay: Duration,
+    max_attempts: Option<u32>,
+}
+
+impl ReconnectOptions {
+    /// Create the default options: The initial delay is 1 second, the maximum delay is 1 minute,
+    /// and the number of attempts is unlimited.
+    pub fn new() -> Self {
+        Self {
+            initial_delay: Duration::from_secs(1),
+            max_delay: Duration::from_secs(60),
+            max_attempts: None,
+        }
+    }
+
+    /// Set the delay after the first failed attempt.
+    pub fn initial_delay(mut self, delay: Duration) -> Self {
+        self.initial_delay = delay;
+        self
+    }
+
+    /// Set the maximum delay between attempts.
+    pub fn max_delay(mut self, delay: Duration) -> Self {
+        self.max_delay = delay;
+        self
+    }
+
+    /// Set the maximum number of consecutive attempts after which the client gives up and
+    /// closes.
+    pub fn max_attempts(mut self, attempts: u32) -> Self {
+        self.max_attempts = Some(attempts);
+        self
+    }
+}
+
+impl Default for ReconnectOptions {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// State required for reconnecting.
+pub(super) struct Reconnect<C> {
+    pub(super) connector: Connector<C>,
+    pub(super) password: Option<String>,
+    pub(super) options: ReconnectOptions,
+    /// The partition to switch into after reconnecting, shared with the client.
+    pub(super) partition: Arc<Mutex<Option<String>>>,
+    /// The observer installed on the client, also shared with the client.
+    pub(super) observer: ObserverSlot,
+}
+
+impl<C> Reconnect<C>
+where
+    C: AsyncRead + AsyncWrite + Unpin,
+{
+    /// Attempt to reestablish the connection.
+    ///
+    /// Commands received while reconnecting (both during an attempt and while waiting between
+    /// attempts) are rejected with [`CommandError::Reconnecting`], and the progress is reported
+    /// on `events`. Returns the reason to report for disconnecting if the client was closed or
+    /// dropped, the maximum number of attempts was reached, or the password was rejected.
+    pub(super) async fn run(
+        &mut self,
+        commands: &mut Receiver<(RawCommandList, CommandResponder)>,
+        close: &mut watch::Receiver<bool>,
+        events: &EventsSender,
+    ) -> Result<AsyncConnection<Metered<C>>, DisconnectReason> {
+        let mut delay = self.options.initial_delay;
+        let mut attempts = 0;
+
+        loop {
+            if *close.borrow() {
+                debug!("client closed while reconnecting");
+                return Err(DisconnectReason::Closed);
+            }
+
+            attempts += 1;
+            debug!(attempts, "attempting to reconnect");
+            events.send_replace(ConnectionEvent::Reconnecting { attempt: attempts });
+
+            let result = tokio::select! {
+                result = self.attempt() => result,
+                Ok(()) = close.changed() => return Err(DisconnectReason::Closed),
+                () = reject_commands(commands) => return Err(stopped_reason(close)),
+            };
+
+            match result {
+                Ok(connection) => {
+                    debug!(attempts, "reconnected successfully");
+                    self.observer.notify(|o| o.reconnected(attempts));
+                    return Ok(connection);
+                }
+                Err(ConnectWithPasswordError::IncorrectPassword) => {
+                    error!("password rejected while reconnecting");
+                    return Err(DisconnectReason::ReconnectFailed);
+                }
+                Err(ConnectWithPasswordError::ProtocolError(e)) => {
+                    warn!(error = ?e, attempts, "failed to reconnect");
+                }
+            }
+
+            if matches!(self.options.max_attempts, Some(max) if attempts >= max) {
+                error!(attempts, "giving up on reconnecting");
+                return Err(DisconnectReason::ReconnectFailed);
+            }
+
+            tokio::select! {
+                _ = sleep(delay) => {}
+                Ok(()) = close.changed() => return Err(DisconnectReason::Closed),
+                () = reject_commands(commands) => return Err(stopped_reason(close)),
+            }
+
+            delay = min(delay * 2, self.options.max_delay);
+        }
+    }
+
+    async fn attempt(&mut self) -> Result<AsyncConnection<Metered<C>>, ConnectWithPasswordError> {
+        let io = (self.connector)().await.map_err(MpdProtocolError::from)?;
+        let io = Metered::new(io, self.observer.clone());
+        let mut connection = handshake(io, self.password.as_deref()).await?;
+
+        let partition = self.partition.lock().unwrap().clone();
+        if let Some(partition) = partition {
+            debug!(?partition, "restoring partition");
+            connection
+                .send(RawCommand::new("partition").argument(partition))
+                .await?;
+
+            match connection.receive().await? {
+                Some(response) if response.is_error() => {
+                    // The partition may have been deleted in the meantime
+                    warn!("failed to restore partition");
+                }
+                Some(_) => (),
+                None => {
+                    return Err(MpdProtocolError::Io(io::Error::new(
+                        io::ErrorKind::UnexpectedEof,
+                        "connection closed while restoring partition",
+                    ))
+                    .into())
+                }
+            }
+        }
+
+        Ok(connection)
+    }
+}
+
+/// The reason to report when all clients are gone, which may be because the last one was closed.
+fn stopped_reason(close: &watch::Receiver<bool>) -> DisconnectReason {
+    if *close.borrow() {
+        DisconnectReason::Closed
+    } else {
+        DisconnectReason::ClientDropped
+    }
+}
+
+/// Reject all received commands with [`CommandError::Reconnecting`], until the client is dropped.
+async fn reject_commands(commands: &mut Receiver<(RawCommandList, CommandResponder)>) {
+    while let Some((_, responder)) = commands.recv().await {
+        let _ = responder.send(Err(CommandError::Reconnecting));
+    }
+}
@@ -0,0 +1,275 @@
+use tokio::{
+    io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt},
+    net::TcpStream,
+};
+
+use std::io;
+use std::net::IpAddr;
+
+const VERSION: u8 = 5;
+const METHOD_NO_AUTH: u8 = 0x00;
+const METHOD_PASSWORD: u8 = 0x02;
+const METHOD_UNACCEPTABLE: u8 = 0xff;
+const COMMAND_CONNECT: u8 = 0x01;
+const ADDRESS_IPV4: u8 = 0x01;
+const ADDRESS_DOMAIN: u8 = 0x03;
+const ADDRESS_IPV6: u8 = 0x04;
+
+/// A SOCKS5 proxy to [connect through](crate::Client::connect_socks5).
+///
+/// Host names are resolved by the proxy, so this works for reaching servers that are only
+/// resolvable from the proxy (such as Tor onion services).
+///
+/// To send a password or [reconnect](crate::Client::connect_with_reconnect) through the proxy,
+/// use [`Socks5Proxy::connect`] to establish the connection:
+///
+/// ```no_run
+/// use mpd_client::{Client, ReconnectOptions, Socks5Proxy};
+///
+/// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+/// let proxy = Socks5Proxy::new("localhost:9050");
+///
+/// let (client, state_changes) = Client::connect_with_reconnect(
+///     move || {
+///         let proxy = proxy.clone();
+///         Box::pin(async move { proxy.connect("music.onion", 6600).await })
+///     },
+///     Some(String::from("hunter2")),
+///     ReconnectOptions::new(),
+/// )
+/// .await?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, PartialEq, Eq)]
+pub struct Socks5Proxy {
+    address: String,
+    credentials: Option<(String, String)>,
+}
+
+impl Socks5Proxy {
+    /// Use the proxy listening on the given address (e.g. `localhost:1080`), without
+    /// authentication.
+    pub fn new(address: impl Into<String>) -> Self {
+        Self {
+            address: address.into(),
+            credentials: None,
+        }
+    }
+
+    /// Authenticate to the proxy using the given username and password.
+    pub fn credentials(mut self, username: impl Into<String>, password: impl Into<String>) -> Self {
+        self.credentials = Some((username.into(), password.into()));
+        self
+    }
+
+    /// Connect to the given host and port through the proxy.
+    ///
+    /// The returned stream can be passed to any of the functions establishing a
+    /// [`Client`](crate::Client) from an existing connection.
+    ///
+    /// # Errors
+    ///
+    /// This will return an error if connecting to the proxy fails, or if the proxy fails to
+    /// connect to the server.
+    pub async fn connect(&self, host: &str, port: u16) -> io::Result<TcpStream> {
+        let mut stream = TcpStream::connect(self.address.as_str()).await?;
+        self.handshake(&mut stream, host, port).await?;
+        Ok(stream)
+    }
+
+    /// Perform the SOCKS5 handshake on the given stream to the proxy, asking it to connect to the
+    /// given target.
+    async fn handshake<S>(&self, stream: &mut S, host: &str, port: u16) -> io::Result<()>
+    where
+        S: AsyncRead + AsyncWrite + Unpin,
+    {
+        let method = match self.credentials {
+            Some(_) => METHOD_PASSWORD,
+            None => METHOD_NO_AUTH,
+        };
+
+        stream.write_all(&[VERSION, 1, method]).await?;
+
+        let mut reply = [0; 2];
+        stream.read_exact(&mut reply).await?;
+
+        if reply[0] != VERSION {
+            return Err(proxy_error("invalid SOCKS version in proxy response"));
+        }
+
+        match reply[1] {
+            METHOD_NO_AUTH if method == METHOD_NO_AUTH => (),
+            METHOD_PASSWORD if method == METHOD_PASSWORD => self.authenticate(stream).await?,
+            METHOD_UNACCEPTABLE => {
+                return Err(proxy_error("proxy rejected the authentication method"))
+            }
+            _ => return Err(proxy_error("proxy selected an unsupported method")),
+        }
+
+        let mut request = vec![VERSION, COMMAND_CONNECT, 0];
+
+        match host.parse() {
+            Ok(IpAddr::V4(ip)) => {
+                request.push(ADDRESS_IPV4);
+                request.extend_from_slice(&ip.octets());
+            }
+            Ok(IpAddr::V6(ip)) => {
+                request.push(ADDRESS_IPV6);
+                request.extend_from_slice(&ip.octets());
+            }
+            Err(_) => {
+                let len = u8::try_from(host.len())
+                    .map_err(|_| io::Error::new(io::ErrorKind::InvalidInput, "host too long"))?;
+                request.push(ADDRESS_DOMAIN);
+                request.push(len);
+                request.extend_from_slice(host.as_bytes());
+            }
+        }
+
+        request.extend_from_slice(&port.to_be_bytes());
+        stream.write_all(&request).await?;
+
+        let mut reply = [0; 4];
+        stream.read_exact(&mut reply).await?;
+
+        if reply[0] != VERSION {
+            return Err(proxy_error("invalid SOCKS version in proxy response"));
+        }
+
+        if reply[1] != 0 {
+            return Err(proxy_error(reply_message(reply[1])));
+        }
+
+        // Skip the bound address and port, which are not needed
+        let address_len = match reply[3] {
+            ADDRESS_IPV4 => 4,
+            ADDRESS_IPV6 => 16,
+            ADDRESS_DOMAIN => usize::from(stream.read_u8().await?),
+            _ => return Err(proxy_error("invalid address type in proxy response")),
+        };
+
+        let mut bound = vec![0; address_len + 2];
+        stream.read_exact(&mut bound).await?;
+
+        Ok(())
+    }
+
+    async fn authenticate<S>(&self, stream: &mut S) -> io::Result<()>
+    where
+        S: AsyncRead + AsyncWrite + Unpin,
+    {
+        let (username, password) = self.credentials.as_ref().unwrap();
+
+        let too_long = || io::Error::new(io::ErrorKind::InvalidInput, "credentials too long");
+        let username_len = u8::try_from(username.len()).map_err(|_| too_long())?;
+        let password_len = u8::try_from(password.len()).map_err(|_| too_long())?;
+
+        let mut request = vec![1, username_len];
+        request.extend_from_slice(username.as_bytes());
+        request.push(password_len);
+        request.extend_from_slice(password.as_bytes());
+        stream.write_all(&request).await?;
+
+        let mut reply = [0; 2];
+        stream.read_exact(&mut reply).await?;
+
+        if reply[1] != 0 {
+            return Err(proxy_error("proxy rejected the credentials"));
+        }
+
+        Ok(())
+    }
+}
+
+impl std::fmt::Debug for Socks5Proxy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        // Don't leak the password into logs
+        f.debug_struct("Socks5Proxy")
+            .field("address", &self.address)
+            .field("username", &self.credentials.as_ref().map(|(u, _)| u))
+            .finish_non_exhaustive()
+    }
+}
+
+fn reply_message(code: u8) -> &'static str {
+    match code {
+        0x01 => "general SOCKS server failure",
+        0x02 => "connection not allowed by ruleset",
+        0x03 => "network unreachable",
+        0x04 => "host unreachable",
+        0x05 => "connection refused",
+        0x06 => "TTL expired",
+        0x07 => "command not supported by proxy",
+        0x08 => "address type not supported by proxy",
+        _ => "unknown SOCKS error",
+    }
+}
+
+fn proxy_error(message: &'static str) -> io::Error {
+    io::Error::other(message)
+}
+
+#[cfg(test)]
+mod tests {
+    use tokio_test::io::Builder as MockBuilder;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn handshake_domain() {
+        let mut io = MockBuilder::new()
+            .write(&[5, 1, 0])
+            .read(&[5, 0])
+            .write(&[5, 1, 0, 3, 9])
+            .write(b"music.lan")
+            .write(&[0x19, 0xb8])
+            .read(&[5, 0, 0, 1, 127, 0, 0, 1, 0x04, 0x38])
+            .build();
+
+        Socks5Proxy::new("localhost:1080")
+            .handshake(&mut io, "music.lan", 6584)
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn handshake_credentials() {
+        let mut io = MockBuilder::new()
+            .write(&[5, 1, 2])
+            .read(&[5, 2])
+            .write(&[1, 3])
+            .write(b"foo")
+            .write(&[3])
+            .write(b"bar")
+            .read(&[1, 0])
+            .write(&[5, 1, 0, 1, 10, 0, 0, 2, 0x19, 0xb8])
+            .read(&[5, 0, 0, 3, 3])
+            .read(b"lan")
+            .read(&[0x04, 0x38])
+            .build();
+
+        Socks5Proxy::new("localhost:1080")
+            .credentials("foo", "bar")
+            .handshake(&mut io, "10.0.0.2", 6584)
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn handshake_refused() {
+        let mut io = MockBuilder::new()
+            .write(&[5, 1, 0])
+            .read(&[5, 0])
+            .write(&[5, 1, 0, 1, 10, 0, 0, 2, 0x19, 0xb8])
+            .read(&[5, 5, 0, 1])
+            .build();
+
+        let error = Socks5Proxy::new("localhost:1080")
+            .handshake(&mut io, "10.0.0.2", 6584)
+            .await
+            .unwrap_err();
+
+        assert_eq!(error.to_string(), "connection refused");
+    }
+}
@@ -0,0 +1,337 @@
+use tokio::sync::watch;
+use tracing::{debug, trace, warn};
+
+use super::{Client, ConnectionEvent};
+use crate::commands::{self as cmds, responses::SongInQueue, responses::Status};
+use crate::errors::CommandError;
+use crate::state_changes::{StateChanges, Subsystem};
+
+/// Local copy of the player state, kept up to date using state changes.
+///
+/// The tracker loads the [status](cmds::Status), the [current song](cmds::CurrentSong) and the
+/// [queue](cmds::Queue) when it is created, and then reloads them whenever a relevant state change
+/// is received. Changes to the queue are loaded incrementally using [`QueueChanges`]. After the
+/// client [reconnected](Client::connect_with_reconnect), everything is loaded again, since state
+/// changes which occurred while the connection was lost are not reported.
+///
+/// The state is exposed using [watch channels](watch), which always contain the latest state and
+/// can be used to wait for changes.
+///
+/// [`QueueChanges`]: cmds::QueueChanges
+#[derive(Debug)]
+pub struct StateTracker {
+    status: watch::Receiver<Status>,
+    current_song: watch::Receiver<Option<SongInQueue>>,
+    queue: watch::Receiver<Vec<SongInQueue>>,
+}
+
+impl StateTracker {
+    /// Load the current state and start tracking it.
+    ///
+    /// This takes the `state_changes` of the connection, since it needs to receive all of them.
+    /// Tracking stops when the connection is closed, or when the tracker and all receivers
+    /// obtained from it were dropped.
+    ///
+    /// # Panics
+    ///
+    /// Since this spawns a task internally, this will panic when called outside a Tokio runtime.
+    ///
+    /// # Errors
+    ///
+    /// This returns errors in the same conditions as [`Client::command`] if loading the initial
+    /// state fails.
+    pub async fn new(client: Client, state_changes: StateChanges) -> Result<Self, CommandError> {
+        let (status, current_song, queue) = client
+            .command_list((cmds::Status, cmds::CurrentSong, cmds::Queue))
+            .await?;
+
+        let (status_sender, status) = watch::channel(status);
+        let (current_song_sender, current_song) = watch::channel(current_song);
+        let (queue_sender, queue) = watch::channel(queue);
+
+        let tracker = Tracker {
+            client,
+            status: status_sender,
+            current_song: current_song_sender,
+            queue: queue_sender,
+        };
+
+        tokio::spawn(tracker.run(state_changes));
+
+        Ok(Self {
+            status,
+            current_song,
+            queue,
+        })
+    }
+
+    /// Get a receiver for the current status.
+    pub fn status(&self) -> watch::Receiver<Status> {
+        self.status.clone()
+    }
+
+    /// Get a receiver for the current song.
+    pub fn current_song(&self) -> watch::Receiver<Option<SongInQueue>> {
+        self.current_song.clone()
+    }
+
+    /// Get a receiver for the contents of the queue.
+    pub fn queue(&self) -> watch::Receiver<Vec<SongInQueue>> {
+        self.queue.clone()
+    }
+}
+
+/// Background task updating the state.
+struct Tracker {
+    client: Client,
+    status: watch::Sender<Status>,
+    current_song: watch::Sender<Option<SongInQueue>>,
+    queue: watch::Sender<Vec<SongInQueue>>,
+}
+
+impl Tracker {
+    async fn run(self, mut state_changes: StateChanges) {
+        let mut events = self.client.connection_events();
+        events.borrow_and_update();
+
+        // Resolves once the tracker and all receivers obtained from it were dropped
+        let dropped = async {
+            tokio::join!(
+                self.status.closed(),
+                self.current_song.closed(),
+                self.queue.closed()
+            );
+        };
+        tokio::pin!(dropped);
+
+        loop {
+            let reload = tokio::select! {
+                () = &mut dropped => {
+                    debug!("state tracker dropped, stopping");
+                    break;
+                }
+                Ok(()) = events.changed() => {
+                    if !matches!(*events.borrow_and_update(), ConnectionEvent::Connected { .. }) {
+                        continue;
+                    }
+
+                    debug!("reconnected, reloading tracked state");
+                    Reload::All
+                }
+                state_change = state_changes.rx.recv() => match state_change {
+                    Some(Ok(Subsystem::Queue)) => Reload::QueueChanges,
+                    Some(Ok(
+                        Subsystem::Player
+                        | Subsystem::Mixer
+                        | Subsystem::Options
+                        | Subsystem::Update
+                        | Subsystem::Partition,
+                    )) => Reload::Status,
+                    Some(Ok(_)) => continue,
+                    Some(Err(e)) => {
+                        warn!(error = ?e, "state change error while tracking state");
+                        continue;
+                    }
+                    None => break,
+                },
+            };
+
+            match self.update(reload).await {
+                Ok(()) => trace!(?reload, "updated tracked state"),
+                Err(CommandError::ConnectionClosed) => break,
+                Err(e) => warn!(error = ?e, "failed to update tracked state"),
+            }
+        }
+    }
+
+    async fn update(&self, reload: Reload) -> Result<(), CommandError> {
+        let (status, current_song) = match reload {
+            Reload::All => {
+                let (status, current_song, queue) = self
+                    .client
+                    .command_list((cmds::Status, cmds::CurrentSong, cmds::Queue))
+                    .await?;
+                let _ = self.queue.send(queue);
+
+                (status, current_song)
+            }
+            Reload::QueueChanges => {
+                let version = self.status.borrow().playlist_version;
+                let (status, current_song, changes) = self
+                    .client
+                    .command_list((
+                        cmds::Status,
+                        cmds::CurrentSong,
+                        cmds::QueueChanges::new(version),
+                    ))
+                    .await?;
+
+                let mut queue = self.queue.borrow().clone();
+                apply_queue_changes(&mut queue, changes, status.playlist_length);
+                let _ = self.queue.send(queue);
+
+                (status, current_song)
+            }
+            Reload::Status => {
+                self.client
+                    .command_list((cmds::Status, cmds::CurrentSong))
+                    .await?
+            }
+        };
+
+        let _ = self.status.send(status);
+        let _ = self.current_song.send(current_song);
+
+        Ok(())
+    }
+}
+
+/// Parts of the state to load again.
+#[derive(Clone, Copy, Debug)]
+enum Reload {
+    /// The status and the current song.
+    Status,
+    /// The status, the current song, and the changes to the queue.
+    QueueChanges,
+    /// Everything, including the entire queue.
+    All,
+}
+
+/// Apply the songs returned by `plchanges` to the previous queue.
+fn apply_queue_changes(queue: &mut Vec<SongInQueue>, changes: Vec<SongInQueue>, length: usize) {
+    queue.truncate(length);
+
+    for song in changes {
+        match queue.get_mut(song.position.0) {
+            Some(existing) => *existing = song,
+            None => queue.push(song),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::SongId;
+    use crate::{DisconnectReason, ReconnectOptions};
+    use std::future::Future;
+    use std::pin::Pin;
+    use std::time::Duration;
+    use tokio_test::{assert_ok, io::Builder as MockBuilder};
+
+    static GREETING: &[u8] = b"OK MPD 0.23.5\n";
+
+    #[tokio::test]
+    async fn state_tracker() {
+        let io = MockBuilder::new()
+            .read(GREETING)
+            .write(b"idle\n")
+            .write(b"noidle\n")
+            .read(b"OK\n")
+            .write(b"command_list_ok_begin\nstatus\ncurrentsong\nplaylistinfo\ncommand_list_end\n")
+            .read(b"repeat: 0\nrandom: 0\nconsume: 0\nstate: stop\nplaylist: 3\nplaylistlength: 2\nlist_OK\n")
+            .read(b"list_OK\n")
+            .read(b"file: a.flac\nPos: 0\nId: 1\nfile: b.flac\nPos: 1\nId: 2\nlist_OK\nOK\n")
+            .write(b"idle\n")
+            .read(b"changed: playlist\nOK\n")
+            .write(b"idle\n")
+            .write(b"noidle\n")
+            .read(b"OK\n")
+            .write(b"command_list_ok_begin\nstatus\ncurrentsong\nplchanges 3\ncommand_list_end\n")
+            .read(b"repeat: 0\nrandom: 0\nconsume: 0\nstate: stop\nplaylist: 4\nplaylistlength: 1\nlist_OK\n")
+            .read(b"list_OK\n")
+            .read(b"file: c.flac\nPos: 0\nId: 3\nlist_OK\nOK\n")
+            .write(b"idle\n")
+            .build();
+
+        let (client, state_changes) = Client::connect(io).await.expect("connect failed");
+        let tracker = assert_ok!(StateTracker::new(client, state_changes).await);
+
+        let mut queue = tracker.queue();
+        let ids = |queue: &[SongInQueue]| queue.iter().map(|s| s.id).collect::<Vec<_>>();
+        assert_eq!(ids(&queue.borrow()), vec![SongId(1), SongId(2)]);
+
+        assert_ok!(queue.changed().await);
+        assert_eq!(ids(&queue.borrow()), vec![SongId(3)]);
+        assert_eq!(tracker.status().borrow().playlist_version, 4);
+        assert_eq!(*tracker.current_song().borrow(), None);
+    }
+
+    #[tokio::test]
+    async fn reload_after_reconnecting() {
+        let first = MockBuilder::new()
+            .read(GREETING)
+            .write(b"idle\n")
+            .write(b"noidle\n")
+            .read(b"OK\n")
+            .write(b"command_list_ok_begin\nstatus\ncurrentsong\nplaylistinfo\ncommand_list_end\n")
+            .read(b"repeat: 0\nrandom: 0\nconsume: 0\nstate: stop\nplaylist: 3\nplaylistlength: 2\nlist_OK\n")
+            .read(b"list_OK\n")
+            .read(b"file: a.flac\nPos: 0\nId: 1\nfile: b.flac\nPos: 1\nId: 2\nlist_OK\nOK\n")
+            .write(b"idle\n")
+            .build();
+        let second = MockBuilder::new()
+            .read(GREETING)
+            .write(b"idle\n")
+            .write(b"noidle\n")
+            .read(b"OK\n")
+            .write(b"command_list_ok_begin\nstatus\ncurrentsong\nplaylistinfo\ncommand_list_end\n")
+            .read(b"repeat: 0\nrandom: 0\nconsume: 0\nstate: stop\nplaylist: 7\nplaylistlength: 1\nlist_OK\n")
+            .read(b"list_OK\n")
+            .read(b"file: c.flac\nPos: 0\nId: 3\nlist_OK\nOK\n")
+            .write(b"idle\n")
+            .wait(Duration::from_secs(3600))
+            .build();
+
+        let mut connections = vec![second, first];
+        let connect = move || {
+            let io = connections.pop().expect("too many connection attempts");
+            Box::pin(async move { Ok(io) }) as Pin<Box<dyn Future<Output = _> + Send>>
+        };
+
+        let (client, state_changes) =
+            Client::connect_with_reconnect(connect, None, ReconnectOptions::new())
+                .await
+                .expect("connect failed");
+        let tracker = assert_ok!(StateTracker::new(client, state_changes).await);
+
+        let mut queue = tracker.queue();
+        let ids = |queue: &[SongInQueue]| queue.iter().map(|s| s.id).collect::<Vec<_>>();
+        assert_eq!(ids(&queue.borrow_and_update()), vec![SongId(1), SongId(2)]);
+
+        // The server closes the first connection, and the state is loaded from the second one
+        assert_ok!(queue.changed().await);
+        assert_eq!(ids(&queue.borrow()), vec![SongId(3)]);
+        assert_eq!(tracker.status().borrow().playlist_version, 7);
+    }
+
+    #[tokio::test]
+    async fn dropping_stops_tracking() {
+        let io = MockBuilder::new()
+            .read(GREETING)
+            .write(b"idle\n")
+            .write(b"noidle\n")
+            .read(b"OK\n")
+            .write(b"command_list_ok_begin\nstatus\ncurrentsong\nplaylistinfo\ncommand_list_end\n")
+            .read(b"repeat: 0\nrandom: 0\nconsume: 0\nstate: stop\nlist_OK\nlist_OK\nlist_OK\nOK\n")
+            .write(b"idle\n")
+            // Keep the connection open without any state changes
+            .wait(Duration::from_secs(3600))
+            .build();
+
+        let (client, state_changes) = Client::connect(io).await.expect("connect failed");
+        let mut events = client.connection_events();
+        let tracker = assert_ok!(StateTracker::new(client, state_changes).await);
+
+        drop(tracker);
+
+        // The connection is closed once the task drops its client
+        while events.changed().await.is_ok() {}
+        assert_eq!(
+            *events.borrow(),
+            ConnectionEvent::Disconnected {
+                reason: DisconnectReason::ClientDropped
+            }
+        );
+    }
+}
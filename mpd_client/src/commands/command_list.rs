@@ -19,6 +19,10 @@ pub trait CommandList: sealed::Sealed {
     #[doc(hidden)]
     fn into_raw_command_list(self) -> Option<RawCommandList>;
 
+    /// The highest minimum protocol version required by the contained commands.
+    #[doc(hidden)]
+    fn min_protocol_version(&self) -> Option<(u32, u32, u32)>;
+
     /// Parse the raw response frames into the proper types.
     #[doc(hidden)]
     fn parse_responses(frames: Vec<Frame>) -> Result<Self::Response, TypedResponseError>;
@@ -43,6 +47,10 @@ where
         Some(raw_commands)
     }
 
+    fn min_protocol_version(&self) -> Option<(u32, u32, u32)> {
+        self.iter().filter_map(C::min_protocol_version).max()
+    }
+
     fn parse_responses(frames: Vec<Frame>) -> Result<Self::Response, TypedResponseError> {
         let frames = frames.into_iter();
         let (lower, _) = frames.size_hint();
@@ -86,6 +94,17 @@ macro_rules! impl_command_list_tuple {
                 Some(commands)
             }
 
+            fn min_protocol_version(&self) -> Option<(u32, u32, u32)> {
+                #[allow(unused_mut)]
+                let mut version = self.0.min_protocol_version();
+
+                $(
+                    version = version.max(self.$further_idx.min_protocol_version());
+                )*
+
+                version
+            }
+
             fn parse_responses(frames: Vec<Frame>) -> Result<Self::Response, TypedResponseError> {
                 let mut frames = frames.into_iter();
 
@@ -103,6 +122,7 @@ macro_rules! impl_command_list_tuple {
 impl_command_list_tuple!(A,);
 impl_command_list_tuple!(A, B => 1);
 impl_command_list_tuple!(A, B => 1, C => 2);
+impl_command_list_tuple!(A, B => 1, C => 2, D => 3);
 impl_command_list_tuple!(A, B => 1, C => 2, D => 3, E => 4);
 impl_command_list_tuple!(A, B => 1, C => 2, D => 3, E => 4, F => 5);
 impl_command_list_tuple!(A, B => 1, C => 2, D => 3, E => 4, F => 5, G => 6);
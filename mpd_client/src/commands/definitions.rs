@@ -8,7 +8,8 @@ use std::ops::{Bound, RangeBounds};
 use std::time::Duration;
 
 use crate::commands::{
-    responses as res, Command, SeekMode, SingleMode, Song, SongId, SongPosition,
+    responses as res, Command, ReplayGainMode, SaveMode, SeekMode, SingleMode, Song, SongId,
+    SongPosition, StickerOperator, StickerSort, StickerType, UpdateJob,
 };
 use crate::raw::RawCommand;
 use crate::tag::Tag;
@@ -23,6 +24,9 @@ macro_rules! argless_command {
         $item
     };
     ($name:ident, $command:literal, $response:ty) => {
+        argless_command!($name, $command, $response, requires None);
+    };
+    ($name:ident, $command:literal, $response:ty, requires $version:expr) => {
         argless_command!(
             #[doc = concat!("`", $command, "` command.")],
             pub struct $name;
@@ -34,6 +38,10 @@ macro_rules! argless_command {
             fn into_command(self) -> RawCommand {
                 RawCommand::new($command)
             }
+
+            fn min_protocol_version(&self) -> Option<(u32, u32, u32)> {
+                $version
+            }
         }
     };
 }
@@ -64,8 +72,101 @@ macro_rules! single_arg_command {
     };
 }
 
+/// Generate the builder methods of commands containing a `query: Query` field, and optionally a
+/// `position` in the queue.
+macro_rules! query_builders {
+    ($name:ident) => {
+        impl $name {
+            /// Sort the songs by the given tag in ascending order.
+            ///
+            /// This does some special-casing for certain tags, see the [MPD documentation][0] for
+            /// details.
+            ///
+            /// # Panics
+            ///
+            /// This will panic when sending the command if you pass a malformed value using the
+            /// [`Other`][error] variant.
+            ///
+            /// [0]: https://www.musicpd.org/doc/html/protocol.html#command-find
+            /// [error]: crate::tag::Tag::Other
+            pub fn sort(mut self, sort_by: Tag) -> Self {
+                self.query.sort = Some(Sort {
+                    tag: sort_by,
+                    descending: false,
+                });
+                self
+            }
+
+            /// Sort the songs by the given tag in descending order.
+            ///
+            /// See [`sort`](Self::sort).
+            pub fn sort_descending(mut self, sort_by: Tag) -> Self {
+                self.query.sort = Some(Sort {
+                    tag: sort_by,
+                    descending: true,
+                });
+                self
+            }
+
+            /// Limit the matched songs to the given window.
+            pub fn window<R>(mut self, window: R) -> Self
+            where
+                R: RangeBounds<usize>,
+            {
+                self.query.window = Some(SongRange::new_usize(window));
+                self
+            }
+        }
+    };
+    ($name:ident, queue_position) => {
+        query_builders!($name);
+
+        impl $name {
+            /// Add the songs at the given position in the queue.
+            ///
+            /// **NOTE**: Supported on protocol versions 0.23.4 and later.
+            pub fn at<P: Into<SongPosition>>(mut self, position: P) -> Self {
+                self.position = Some(PositionOrRelative::Absolute(position.into()));
+                self
+            }
+
+            /// Add the songs `delta` positions before the current song.
+            ///
+            /// **NOTE**: Supported on protocol versions 0.23.4 and later.
+            pub fn before_current(mut self, delta: usize) -> Self {
+                self.position = Some(PositionOrRelative::BeforeCurrent(delta));
+                self
+            }
+
+            /// Add the songs `delta` positions after the current song.
+            ///
+            /// **NOTE**: Supported on protocol versions 0.23.4 and later.
+            pub fn after_current(mut self, delta: usize) -> Self {
+                self.position = Some(PositionOrRelative::AfterCurrent(delta));
+                self
+            }
+        }
+    };
+}
+
 argless_command!(Ping, "ping", res::Empty);
 
+/// `kill` command.
+///
+/// Shut down the server. The server closes the connection without responding, so sending this
+/// command results in a [`CommandError::ClosedByServer`](crate::CommandError::ClosedByServer)
+/// error if it succeeded.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Kill;
+
+impl Command for Kill {
+    type Response = res::Empty;
+
+    fn into_command(self) -> RawCommand {
+        RawCommand::new("kill")
+    }
+}
+
 argless_command!(Next, "next", res::Empty);
 argless_command!(Previous, "previous", res::Empty);
 argless_command!(Stop, "stop", res::Empty);
@@ -74,23 +175,131 @@ argless_command!(ClearQueue, "clear", res::Empty);
 argless_command!(Status, "status", res::Status);
 argless_command!(Stats, "stats", res::Stats);
 
+/// `config` command.
+///
+/// **NOTE**: The server only permits this on local socket connections.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Config;
+
+impl Command for Config {
+    type Response = res::Config;
+
+    fn into_command(self) -> RawCommand {
+        RawCommand::new("config")
+    }
+}
+
+argless_command!(ReplayGainStatus, "replay_gain_status", ReplayGainMode);
+
 argless_command!(Queue, "playlistinfo", Vec<res::SongInQueue>);
 argless_command!(CurrentSong, "currentsong", Option<res::SongInQueue>);
 
+/// `playlistid` command.
+///
+/// Get the song with the given ID in the queue. Use [`Queue`] to get the entire queue.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct QueueSongById(pub SongId);
+
+impl Command for QueueSongById {
+    type Response = Option<res::SongInQueue>;
+
+    fn into_command(self) -> RawCommand {
+        RawCommand::new("playlistid").argument(self.0)
+    }
+}
+
 argless_command!(GetPlaylists, "listplaylists", Vec<res::Playlist>);
 
 argless_command!(EnabledTagTypes, "tagtypes", Vec<Tag>);
 
+/// `protocol` command.
+///
+/// List the protocol features which are currently enabled for this connection.
+///
+/// **NOTE**: Supported on protocol versions 0.24 and later.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct EnabledProtocolFeatures;
+
+impl Command for EnabledProtocolFeatures {
+    type Response = Vec<String>;
+
+    fn into_command(self) -> RawCommand {
+        RawCommand::new("protocol")
+    }
+
+    fn min_protocol_version(&self) -> Option<(u32, u32, u32)> {
+        Some((0, 24, 0))
+    }
+}
+
+/// `protocol available` command.
+///
+/// List the protocol features supported by the server, which can be enabled using
+/// [`ProtocolFeatures`].
+///
+/// **NOTE**: Supported on protocol versions 0.24 and later.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct AvailableProtocolFeatures;
+
+impl Command for AvailableProtocolFeatures {
+    type Response = Vec<String>;
+
+    fn into_command(self) -> RawCommand {
+        RawCommand::new("protocol").argument("available")
+    }
+
+    fn min_protocol_version(&self) -> Option<(u32, u32, u32)> {
+        Some((0, 24, 0))
+    }
+}
+
+argless_command!(Outputs, "outputs", Vec<res::Output>);
+
+argless_command!(ListPartitions, "listpartitions", Vec<String>);
+
+argless_command!(ListMounts, "listmounts", Vec<res::Mount>);
+
+argless_command!(Channels, "channels", Vec<String>);
+argless_command!(ReadMessages, "readmessages", Vec<res::ChannelMessage>);
+
+argless_command!(Commands, "commands", Vec<String>);
+argless_command!(NotCommands, "notcommands", Vec<String>);
+argless_command!(UrlHandlers, "urlhandlers", Vec<String>);
+argless_command!(Decoders, "decoders", Vec<res::Decoder>);
+
+argless_command!(StickerNames, "stickernames", Vec<String>, requires Some((0, 24, 0)));
+argless_command!(StickerTypes, "stickertypes", Vec<StickerType>, requires Some((0, 24, 0)));
+
 single_arg_command!(SetRandom, bool, "random", res::Empty);
-single_arg_command!(SetConsume, bool, "consume", res::Empty);
 single_arg_command!(SetRepeat, bool, "repeat", res::Empty);
 single_arg_command!(SetPause, bool, "pause", res::Empty);
+single_arg_command!(
+    SetReplayGainMode,
+    ReplayGainMode,
+    "replay_gain_mode",
+    res::Empty
+);
 
-single_arg_command!(SaveQueueAsPlaylist, String, "save", res::Empty);
 single_arg_command!(DeletePlaylist, String, "rm", res::Empty);
 single_arg_command!(GetPlaylist, String, "listplaylistinfo", Vec<res::Song>);
 single_arg_command!(ClearPlaylist, String, "playlistclear", res::Empty);
 
+single_arg_command!(SwitchPartition, String, "partition", res::Empty);
+single_arg_command!(NewPartition, String, "newpartition", res::Empty);
+single_arg_command!(DeletePartition, String, "delpartition", res::Empty);
+single_arg_command!(MoveOutput, String, "moveoutput", res::Empty);
+
+single_arg_command!(Unmount, String, "unmount", res::Empty);
+
+single_arg_command!(Subscribe, String, "subscribe", res::Empty);
+single_arg_command!(Unsubscribe, String, "unsubscribe", res::Empty);
+
+single_arg_command!(EnableOutput, u32, "enableoutput", res::Empty);
+single_arg_command!(DisableOutput, u32, "disableoutput", res::Empty);
+single_arg_command!(ToggleOutput, u32, "toggleoutput", res::Empty);
+
+single_arg_command!(ReadComments, String, "readcomments", Vec<(String, String)>);
+
 /// `crossfade` command.
 ///
 /// The given duration is truncated to the seconds.
@@ -102,7 +311,7 @@ impl Command for Crossfade {
 
     fn into_command(self) -> RawCommand {
         let seconds = self.0.as_secs();
-        RawCommand::new("crossfade").argument(seconds.to_string())
+        RawCommand::new("crossfade").argument(seconds)
     }
 }
 
@@ -117,7 +326,41 @@ impl Command for SetVolume {
 
     fn into_command(self) -> RawCommand {
         let volume = min(self.0, 100);
-        RawCommand::new("setvol").argument(volume.to_string())
+        RawCommand::new("setvol").argument(volume)
+    }
+}
+
+/// `volume` command.
+///
+/// Change the volume by the given amount, relative to the current volume.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ChangeVolume(pub i8);
+
+impl Command for ChangeVolume {
+    type Response = res::Empty;
+
+    fn into_command(self) -> RawCommand {
+        RawCommand::new("volume").argument(format!("{:+}", self.0))
+    }
+}
+
+/// `getvol` command.
+///
+/// Get the current volume. The response contains no volume if the server has no mixer.
+///
+/// **NOTE**: Supported on protocol versions 0.23 and later.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct GetVolume;
+
+impl Command for GetVolume {
+    type Response = res::Volume;
+
+    fn into_command(self) -> RawCommand {
+        RawCommand::new("getvol")
+    }
+
+    fn min_protocol_version(&self) -> Option<(u32, u32, u32)> {
+        Some((0, 23, 0))
     }
 }
 
@@ -129,13 +372,28 @@ impl Command for SetSingle {
     type Response = res::Empty;
 
     fn into_command(self) -> RawCommand {
-        let single = match self.0 {
-            SingleMode::Disabled => "0",
-            SingleMode::Enabled => "1",
-            SingleMode::Oneshot => "oneshot",
-        };
+        RawCommand::new("single").argument(self.0)
+    }
+}
+
+/// `consume` command.
+///
+/// **NOTE**: [`SingleMode::Oneshot`] is only supported on protocol versions 0.24 and later.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SetConsume(pub SingleMode);
 
-        RawCommand::new("single").argument(single)
+impl Command for SetConsume {
+    type Response = res::Empty;
+
+    fn into_command(self) -> RawCommand {
+        RawCommand::new("consume").argument(self.0)
+    }
+
+    fn min_protocol_version(&self) -> Option<(u32, u32, u32)> {
+        match self.0 {
+            SingleMode::Oneshot => Some((0, 24, 0)),
+            _ => None,
+        }
     }
 }
 
@@ -284,6 +542,78 @@ impl Command for Add {
 
         command
     }
+
+    fn min_protocol_version(&self) -> Option<(u32, u32, u32)> {
+        match self.position {
+            Some(PositionOrRelative::BeforeCurrent(_) | PositionOrRelative::AfterCurrent(_)) => {
+                Some((0, 23, 0))
+            }
+            _ => None,
+        }
+    }
+}
+
+/// `add` command.
+///
+/// Add a file or a directory to the queue. Directories are added recursively. Unlike [`Add`],
+/// no song ID is returned. If neither of [`AddRecursive::at`], [`AddRecursive::before_current`],
+/// or [`AddRecursive::after_current`] is used, the songs will be appended to the queue.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct AddRecursive {
+    uri: String,
+    position: Option<PositionOrRelative>,
+}
+
+impl AddRecursive {
+    /// Add the file or directory with the given URI.
+    pub fn uri(uri: String) -> Self {
+        Self {
+            uri,
+            position: None,
+        }
+    }
+
+    /// Add the URI at the given position in the queue.
+    ///
+    /// **NOTE**: Supported on protocol versions later than 0.23.
+    pub fn at<P: Into<SongPosition>>(mut self, position: P) -> Self {
+        self.position = Some(PositionOrRelative::Absolute(position.into()));
+        self
+    }
+
+    /// Add the URI `delta` positions before the current song.
+    ///
+    /// **NOTE**: Supported on protocol versions later than 0.23.
+    pub fn before_current(mut self, delta: usize) -> Self {
+        self.position = Some(PositionOrRelative::BeforeCurrent(delta));
+        self
+    }
+
+    /// Add the URI `delta` positions after the current song.
+    ///
+    /// **NOTE**: Supported on protocol versions later than 0.23.
+    pub fn after_current(mut self, delta: usize) -> Self {
+        self.position = Some(PositionOrRelative::AfterCurrent(delta));
+        self
+    }
+}
+
+impl Command for AddRecursive {
+    type Response = res::Empty;
+
+    fn into_command(self) -> RawCommand {
+        let mut command = RawCommand::new("add").argument(self.uri);
+
+        if let Some(pos) = self.position {
+            command.add_argument(pos).unwrap();
+        }
+
+        command
+    }
+
+    fn min_protocol_version(&self) -> Option<(u32, u32, u32)> {
+        self.position.map(|_| (0, 23, 0))
+    }
 }
 
 /// `delete` and `deleteid` commands.
@@ -463,174 +793,268 @@ impl Command for Move {
     }
 }
 
-/// `find` command.
-#[derive(Clone, Debug, PartialEq, Eq)]
-pub struct Find {
-    filter: Filter,
-    sort: Option<Tag>,
-    window: Option<SongRange>,
+/// `prio` and `prioid` commands.
+///
+/// Songs with a higher priority are played first when random mode is enabled. The default
+/// priority is 0.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SetPriority {
+    target: Target,
+    priority: u8,
 }
 
-impl Find {
-    /// Find all songs matching `filter`.
-    pub fn new(filter: Filter) -> Self {
+impl SetPriority {
+    /// Set the priority of the song with the given ID.
+    pub fn id(id: SongId, priority: u8) -> Self {
         Self {
-            filter,
-            sort: None,
-            window: None,
+            target: Target::Id(id),
+            priority,
         }
     }
 
-    /// Sort the result by the given tag.
-    ///
-    /// This does some special-casing for certain tags, see the [MPD documentation][0] for details.
-    ///
-    /// # Panics
-    ///
-    /// This will panic when sending the command if you pass a malformed value using the
-    /// [`Other`][error] variant.
-    ///
-    /// [0]: https://www.musicpd.org/doc/html/protocol.html#command-find
-    /// [error]: crate::tag::Tag::Other
-    pub fn sort(mut self, sort_by: Tag) -> Self {
-        self.sort = Some(sort_by);
-        self
+    /// Set the priority of the song at the given position.
+    pub fn position(position: SongPosition, priority: u8) -> Self {
+        Self {
+            target: Target::Range(SongRange::new(position..=position)),
+            priority,
+        }
     }
 
-    /// Limit the result to the given window.
-    pub fn window<R>(mut self, window: R) -> Self
+    /// Set the priority of the songs in the given range.
+    pub fn range<R>(range: R, priority: u8) -> Self
     where
-        R: RangeBounds<usize>,
+        R: RangeBounds<SongPosition>,
     {
-        self.window = Some(SongRange::new_usize(window));
-        self
+        Self {
+            target: Target::Range(SongRange::new(range)),
+            priority,
+        }
     }
 }
 
-impl Command for Find {
-    type Response = Vec<res::Song>;
+impl Command for SetPriority {
+    type Response = res::Empty;
 
     fn into_command(self) -> RawCommand {
-        let mut command = RawCommand::new("find").argument(self.filter);
-
-        if let Some(sort) = self.sort {
-            command.add_argument("sort").unwrap();
-            command
-                .add_argument(sort.as_str())
-                .expect("Invalid sort value");
-        }
-
-        if let Some(window) = self.window {
-            command.add_argument("window").unwrap();
-            command.add_argument(window).unwrap();
+        match self.target {
+            Target::Id(id) => RawCommand::new("prioid")
+                .argument(self.priority)
+                .argument(id),
+            Target::Range(range) => RawCommand::new("prio")
+                .argument(self.priority)
+                .argument(range),
         }
-
-        command
     }
 }
 
-/// `list` command.
-#[derive(Clone, Debug, PartialEq, Eq)]
-pub struct List {
-    tag: Tag,
-    filter: Option<Filter>,
-    group_by: Option<Tag>,
+/// `rangeid` command.
+///
+/// Limit playback of a song in the queue to the given time range. The selected range is returned
+/// as part of [`SongInQueue`][res::SongInQueue].
+///
+/// **NOTE**: The song must not be playing currently.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SetSongRange {
+    id: SongId,
+    from: Option<Duration>,
+    to: Option<Duration>,
 }
 
-impl List {
-    /// List distinct values of `tag`.
-    pub fn new(tag: Tag) -> Self {
-        List {
-            tag,
-            filter: None,
-            group_by: None,
-        }
-    }
+impl SetSongRange {
+    /// Only play the given range of the song with the given ID.
+    ///
+    /// Whether the bounds of the range are inclusive or exclusive is ignored.
+    pub fn new<R>(id: SongId, range: R) -> Self
+    where
+        R: RangeBounds<Duration>,
+    {
+        let from = match range.start_bound() {
+            Bound::Excluded(from) | Bound::Included(from) => Some(*from),
+            Bound::Unbounded => None,
+        };
 
-    /// Filter the songs being considered using the given `filter`.
-    pub fn filter(mut self, filter: Filter) -> Self {
-        self.filter = Some(filter);
-        self
+        let to = match range.end_bound() {
+            Bound::Excluded(to) | Bound::Included(to) => Some(*to),
+            Bound::Unbounded => None,
+        };
+
+        Self { id, from, to }
     }
 
-    /// Group results by the given tag.
-    pub fn group_by(mut self, group_by: Tag) -> Self {
-        self.group_by = Some(group_by);
-        self
+    /// Remove the range from the song with the given ID, playing it completely.
+    pub fn clear(id: SongId) -> Self {
+        Self::new(id, ..)
     }
 }
 
-impl Command for List {
-    type Response = res::List;
+impl Command for SetSongRange {
+    type Response = res::Empty;
 
     fn into_command(self) -> RawCommand {
-        let mut command = RawCommand::new("list").argument(self.tag);
-
-        if let Some(filter) = self.filter {
-            command.add_argument(filter).unwrap();
-        }
+        let from = self.from.map(Argument::render).unwrap_or_default();
+        let to = self.to.map(Argument::render).unwrap_or_default();
 
-        if let Some(group_by) = self.group_by {
-            command.add_argument("group").unwrap();
-            command.add_argument(group_by).unwrap();
-        }
-
-        command
+        RawCommand::new("rangeid")
+            .argument(self.id)
+            .argument(format!("{}:{}", from, to))
     }
 }
 
-/// `rename` command.
+/// `addtagid` command.
+///
+/// Add a value to a tag of a song in the queue. The change is not persisted in the database, the
+/// server only permits this for remote songs (e.g. streams).
 #[derive(Clone, Debug, PartialEq, Eq)]
-pub struct RenamePlaylist {
-    from: String,
-    to: String,
+pub struct AddTagToSong {
+    id: SongId,
+    tag: Tag,
+    value: String,
 }
 
-impl RenamePlaylist {
-    /// Rename the playlist named `from` to `to`.
-    pub fn new(from: String, to: String) -> Self {
-        Self { from, to }
+impl AddTagToSong {
+    /// Add `value` to the given `tag` of the song with the given ID.
+    pub fn new(id: SongId, tag: Tag, value: String) -> Self {
+        Self { id, tag, value }
     }
 }
 
-impl Command for RenamePlaylist {
+impl Command for AddTagToSong {
     type Response = res::Empty;
 
     fn into_command(self) -> RawCommand {
-        RawCommand::new("rename")
-            .argument(self.from)
-            .argument(self.to)
+        RawCommand::new("addtagid")
+            .argument(self.id)
+            .argument(self.tag)
+            .argument(self.value)
     }
 }
 
-/// `load` command.
+/// `cleartagid` command.
+///
+/// Remove tags added using [`AddTagToSong`] from a song in the queue.
 #[derive(Clone, Debug, PartialEq, Eq)]
-pub struct LoadPlaylist {
-    name: String,
+pub struct ClearTagsOfSong {
+    id: SongId,
+    tag: Option<Tag>,
+}
+
+impl ClearTagsOfSong {
+    /// Remove all tags from the song with the given ID.
+    pub fn all(id: SongId) -> Self {
+        Self { id, tag: None }
+    }
+
+    /// Remove only the given tag from the song with the given ID.
+    pub fn tag(id: SongId, tag: Tag) -> Self {
+        Self { id, tag: Some(tag) }
+    }
+}
+
+impl Command for ClearTagsOfSong {
+    type Response = res::Empty;
+
+    fn into_command(self) -> RawCommand {
+        let mut command = RawCommand::new("cleartagid").argument(self.id);
+
+        if let Some(tag) = self.tag {
+            command.add_argument(tag).unwrap();
+        }
+
+        command
+    }
+}
+
+/// `shuffle` command.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Shuffle(Option<SongRange>);
+
+impl Shuffle {
+    /// Shuffle the entire queue.
+    pub fn all() -> Self {
+        Self(None)
+    }
+
+    /// Shuffle the given range of the queue.
+    pub fn range<R>(range: R) -> Self
+    where
+        R: RangeBounds<SongPosition>,
+    {
+        Self(Some(SongRange::new(range)))
+    }
+}
+
+impl Command for Shuffle {
+    type Response = res::Empty;
+
+    fn into_command(self) -> RawCommand {
+        let mut command = RawCommand::new("shuffle");
+
+        if let Some(range) = self.0 {
+            command.add_argument(range).unwrap();
+        }
+
+        command
+    }
+}
+
+/// `playlistinfo` command with a range.
+///
+/// Use [`Queue`] to get the entire queue.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct QueueRange(SongRange);
+
+impl QueueRange {
+    /// Get the songs in the given range of the queue.
+    pub fn new<R>(range: R) -> Self
+    where
+        R: RangeBounds<SongPosition>,
+    {
+        Self(SongRange::new(range))
+    }
+}
+
+impl Command for QueueRange {
+    type Response = Vec<res::SongInQueue>;
+
+    fn into_command(self) -> RawCommand {
+        RawCommand::new("playlistinfo").argument(self.0)
+    }
+}
+
+/// `plchanges` command.
+///
+/// Get the songs in the queue which changed since the given queue version, as returned in
+/// [`Status::playlist_version`][res::Status::playlist_version].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct QueueChanges {
+    version: u32,
     range: Option<SongRange>,
 }
 
-impl LoadPlaylist {
-    /// Load the playlist with the given name into the queue.
-    pub fn name(name: String) -> Self {
-        Self { name, range: None }
+impl QueueChanges {
+    /// Get all songs which changed since the given queue version.
+    pub fn new(version: u32) -> Self {
+        Self {
+            version,
+            range: None,
+        }
     }
 
-    /// Limit the loaded playlist to the given window.
+    /// Only return changed songs in the given range of the queue.
     pub fn range<R>(mut self, range: R) -> Self
     where
-        R: RangeBounds<usize>,
+        R: RangeBounds<SongPosition>,
     {
-        self.range = Some(SongRange::new_usize(range));
+        self.range = Some(SongRange::new(range));
         self
     }
 }
 
-impl Command for LoadPlaylist {
-    type Response = res::Empty;
+impl Command for QueueChanges {
+    type Response = Vec<res::SongInQueue>;
 
     fn into_command(self) -> RawCommand {
-        let mut command = RawCommand::new("load").argument(self.name);
+        let mut command = RawCommand::new("plchanges").argument(self.version);
 
         if let Some(range) = self.range {
             command.add_argument(range).unwrap();
@@ -640,307 +1064,1505 @@ impl Command for LoadPlaylist {
     }
 }
 
-/// `playlistadd` command.
+/// `plchangesposid` command.
 ///
-/// If [`AddToPlaylist::at`] is not used, the song will be appended to the playlist.
-#[derive(Clone, Debug, PartialEq, Eq)]
-pub struct AddToPlaylist {
-    playlist: String,
-    song_url: String,
-    position: Option<SongPosition>,
+/// Like [`QueueChanges`], but only returns the positions and IDs of the changed songs.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct QueueChangesIds {
+    version: u32,
+    range: Option<SongRange>,
 }
 
-impl AddToPlaylist {
-    /// Add `song_url` to `playlist`.
-    pub fn new(playlist: String, song_url: String) -> Self {
+impl QueueChangesIds {
+    /// Get all songs which changed since the given queue version.
+    pub fn new(version: u32) -> Self {
         Self {
-            playlist,
-            song_url,
-            position: None,
+            version,
+            range: None,
         }
     }
 
-    /// Add the URI at the given position in the queue.
-    ///
-    /// **NOTE**: Supported on protocol versions later than 0.23.3.
-    pub fn at<P: Into<SongPosition>>(mut self, position: P) -> Self {
-        self.position = Some(position.into());
+    /// Only return changed songs in the given range of the queue.
+    pub fn range<R>(mut self, range: R) -> Self
+    where
+        R: RangeBounds<SongPosition>,
+    {
+        self.range = Some(SongRange::new(range));
         self
     }
 }
 
-impl Command for AddToPlaylist {
-    type Response = res::Empty;
+impl Command for QueueChangesIds {
+    type Response = Vec<(SongPosition, SongId)>;
 
     fn into_command(self) -> RawCommand {
-        let mut command = RawCommand::new("playlistadd")
-            .argument(self.playlist)
-            .argument(self.song_url);
+        let mut command = RawCommand::new("plchangesposid").argument(self.version);
 
-        if let Some(pos) = self.position {
-            command.add_argument(pos).unwrap();
+        if let Some(range) = self.range {
+            command.add_argument(range).unwrap();
         }
 
         command
     }
 }
 
-/// `playlistdelete` command.
+/// `find` command.
 #[derive(Clone, Debug, PartialEq, Eq)]
-pub struct RemoveFromPlaylist {
-    playlist: String,
-    target: PositionOrRange,
+pub struct Find {
+    query: Query,
+}
+
+impl Find {
+    /// Find all songs matching `filter`.
+    pub fn new(filter: Filter) -> Self {
+        Self {
+            query: Query::new(filter),
+        }
+    }
+}
+
+query_builders!(Find);
+
+impl Command for Find {
+    type Response = Vec<res::Song>;
+
+    fn into_command(self) -> RawCommand {
+        self.query.add_to(RawCommand::new("find"))
+    }
+
+    fn min_protocol_version(&self) -> Option<(u32, u32, u32)> {
+        self.query.min_protocol_version()
+    }
 }
 
+/// `playlistfind` command.
+///
+/// Find the songs in the queue matching the given filter.
 #[derive(Clone, Debug, PartialEq, Eq)]
-enum PositionOrRange {
-    Position(usize),
-    Range(SongRange),
+pub struct FindInQueue(pub Filter);
+
+impl Command for FindInQueue {
+    type Response = Vec<res::SongInQueue>;
+
+    fn into_command(self) -> RawCommand {
+        RawCommand::new("playlistfind").argument(self.0)
+    }
+
+    fn min_protocol_version(&self) -> Option<(u32, u32, u32)> {
+        self.0.min_protocol_version()
+    }
 }
 
-impl RemoveFromPlaylist {
-    /// Delete the song at `position` from `playlist`.
-    pub fn position(playlist: String, position: usize) -> Self {
-        RemoveFromPlaylist {
-            playlist,
-            target: PositionOrRange::Position(position),
+/// `playlistsearch` command.
+///
+/// Like [`FindInQueue`], but tag values are matched case-insensitively.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SearchInQueue(pub Filter);
+
+impl Command for SearchInQueue {
+    type Response = Vec<res::SongInQueue>;
+
+    fn into_command(self) -> RawCommand {
+        RawCommand::new("playlistsearch").argument(self.0)
+    }
+
+    fn min_protocol_version(&self) -> Option<(u32, u32, u32)> {
+        self.0.min_protocol_version()
+    }
+}
+
+/// `search` command.
+///
+/// Like [`Find`], but tag values are matched case-insensitively.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Search {
+    query: Query,
+}
+
+impl Search {
+    /// Search for all songs matching `filter`.
+    pub fn new(filter: Filter) -> Self {
+        Self {
+            query: Query::new(filter),
         }
     }
+}
 
-    /// Delete the specified range of songs from `playlist`.
-    pub fn range<R>(playlist: String, range: R) -> Self
-    where
-        R: RangeBounds<SongPosition>,
-    {
-        RemoveFromPlaylist {
-            playlist,
-            target: PositionOrRange::Range(SongRange::new(range)),
+query_builders!(Search);
+
+impl Command for Search {
+    type Response = Vec<res::Song>;
+
+    fn into_command(self) -> RawCommand {
+        self.query.add_to(RawCommand::new("search"))
+    }
+
+    fn min_protocol_version(&self) -> Option<(u32, u32, u32)> {
+        self.query.min_protocol_version()
+    }
+}
+
+/// `findadd` command.
+///
+/// Like [`Find`], but the matched songs are added to the queue instead of being returned.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FindAdd {
+    query: Query,
+    position: Option<PositionOrRelative>,
+}
+
+impl FindAdd {
+    /// Add all songs matching `filter` to the queue.
+    pub fn new(filter: Filter) -> Self {
+        Self {
+            query: Query::new(filter),
+            position: None,
         }
     }
 }
 
-impl Command for RemoveFromPlaylist {
+query_builders!(FindAdd, queue_position);
+
+impl Command for FindAdd {
     type Response = res::Empty;
 
     fn into_command(self) -> RawCommand {
-        let command = RawCommand::new("playlistdelete").argument(self.playlist);
+        let command = self.query.add_to(RawCommand::new("findadd"));
+        add_position(command, self.position)
+    }
 
-        match self.target {
-            PositionOrRange::Position(p) => command.argument(p.to_string()),
-            PositionOrRange::Range(r) => command.argument(r),
-        }
+    fn min_protocol_version(&self) -> Option<(u32, u32, u32)> {
+        let position = self.position.map(|_| (0, 23, 4));
+        self.query.min_protocol_version().max(position)
     }
 }
 
-/// `playlistmove` command.
+/// `searchadd` command.
+///
+/// Like [`Search`], but the matched songs are added to the queue instead of being returned.
 #[derive(Clone, Debug, PartialEq, Eq)]
-pub struct MoveInPlaylist {
-    playlist: String,
-    from: usize,
-    to: usize,
+pub struct SearchAdd {
+    query: Query,
+    position: Option<PositionOrRelative>,
 }
 
-impl MoveInPlaylist {
-    /// Move the song at `from` to `to` in the playlist named `playlist`.
-    pub fn new(playlist: String, from: usize, to: usize) -> Self {
-        Self { playlist, from, to }
+impl SearchAdd {
+    /// Add all songs matching `filter` to the queue.
+    pub fn new(filter: Filter) -> Self {
+        Self {
+            query: Query::new(filter),
+            position: None,
+        }
     }
 }
 
-impl Command for MoveInPlaylist {
+query_builders!(SearchAdd, queue_position);
+
+impl Command for SearchAdd {
     type Response = res::Empty;
 
     fn into_command(self) -> RawCommand {
-        RawCommand::new("playlistmove")
-            .argument(self.playlist)
-            .argument(self.from.to_string())
-            .argument(self.to.to_string())
+        let command = self.query.add_to(RawCommand::new("searchadd"));
+        add_position(command, self.position)
+    }
+
+    fn min_protocol_version(&self) -> Option<(u32, u32, u32)> {
+        let position = self.position.map(|_| (0, 23, 4));
+        self.query.min_protocol_version().max(position)
     }
 }
 
-/// `listallinfo` command.
+/// `searchaddpl` command.
+///
+/// Like [`Search`], but the matched songs are added to a stored playlist instead of being
+/// returned. The playlist is created if it does not exist yet.
 #[derive(Clone, Debug, PartialEq, Eq)]
-pub struct ListAllIn {
-    directory: String,
+pub struct SearchAddToPlaylist {
+    playlist: String,
+    query: Query,
+    position: Option<usize>,
 }
 
-impl ListAllIn {
-    /// List all songs in the library.
-    pub fn root() -> Self {
+impl SearchAddToPlaylist {
+    /// Add all songs matching `filter` to `playlist`.
+    pub fn new(playlist: String, filter: Filter) -> Self {
         Self {
-            directory: String::new(),
+            playlist,
+            query: Query::new(filter),
+            position: None,
         }
     }
 
-    /// List all songs beneath the given directory.
-    pub fn directory(directory: String) -> Self {
-        Self { directory }
+    /// Add the songs at the given position in the playlist.
+    ///
+    /// **NOTE**: Supported on protocol versions 0.23.4 and later.
+    pub fn at(mut self, position: usize) -> Self {
+        self.position = Some(position);
+        self
     }
 }
 
-impl Command for ListAllIn {
-    type Response = Vec<res::Song>;
+query_builders!(SearchAddToPlaylist);
+
+impl Command for SearchAddToPlaylist {
+    type Response = res::Empty;
 
     fn into_command(self) -> RawCommand {
-        let mut command = RawCommand::new("listallinfo");
+        let command = RawCommand::new("searchaddpl").argument(self.playlist);
+        add_position(self.query.add_to(command), self.position)
+    }
 
-        if !self.directory.is_empty() {
+    fn min_protocol_version(&self) -> Option<(u32, u32, u32)> {
+        let position = self.position.map(|_| (0, 23, 4));
+        self.query.min_protocol_version().max(position)
+    }
+}
+
+/// `searchplaylist` command.
+///
+/// Like [`Search`], but searches the songs in the given stored playlist instead of the database.
+///
+/// **NOTE**: Supported on protocol versions 0.24 and later.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SearchPlaylist {
+    playlist: String,
+    filter: Filter,
+    window: Option<SongRange>,
+}
+
+impl SearchPlaylist {
+    /// Search for all songs in `playlist` matching `filter`.
+    pub fn new(playlist: String, filter: Filter) -> Self {
+        Self {
+            playlist,
+            filter,
+            window: None,
+        }
+    }
+
+    /// Limit the result to the given window.
+    pub fn window<R>(mut self, window: R) -> Self
+    where
+        R: RangeBounds<usize>,
+    {
+        self.window = Some(SongRange::new_usize(window));
+        self
+    }
+}
+
+impl Command for SearchPlaylist {
+    type Response = Vec<res::Song>;
+
+    fn into_command(self) -> RawCommand {
+        let mut command = RawCommand::new("searchplaylist")
+            .argument(self.playlist)
+            .argument(self.filter);
+
+        if let Some(window) = self.window {
+            command.add_argument(window).unwrap();
+        }
+
+        command
+    }
+
+    fn min_protocol_version(&self) -> Option<(u32, u32, u32)> {
+        Some((0, 24, 0))
+    }
+}
+
+/// The filter, sort and window arguments shared by the database query commands.
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct Query {
+    filter: Filter,
+    sort: Option<Sort>,
+    window: Option<SongRange>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct Sort {
+    tag: Tag,
+    descending: bool,
+}
+
+impl Query {
+    fn new(filter: Filter) -> Self {
+        Self {
+            filter,
+            sort: None,
+            window: None,
+        }
+    }
+
+    /// Append the arguments to the given command.
+    fn add_to(self, command: RawCommand) -> RawCommand {
+        let mut command = command.argument(self.filter);
+
+        if let Some(sort) = self.sort {
+            command.add_argument("sort").unwrap();
+            command
+                .add_argument(sort.render())
+                .expect("Invalid sort value");
+        }
+
+        if let Some(window) = self.window {
+            command.add_argument("window").unwrap();
+            command.add_argument(window).unwrap();
+        }
+
+        command
+    }
+
+    fn min_protocol_version(&self) -> Option<(u32, u32, u32)> {
+        self.filter.min_protocol_version()
+    }
+}
+
+impl Sort {
+    fn render(self) -> Cow<'static, str> {
+        if self.descending {
+            Cow::Owned(format!("-{}", self.tag.as_str()))
+        } else {
+            self.tag.as_str()
+        }
+    }
+}
+
+/// Append the `position` argument for the commands adding songs, if a position was given.
+fn add_position<P: Argument>(mut command: RawCommand, position: Option<P>) -> RawCommand {
+    if let Some(pos) = position {
+        command.add_argument("position").unwrap();
+        command.add_argument(pos).unwrap();
+    }
+
+    command
+}
+
+/// `list` command.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct List {
+    tag: Tag,
+    filter: Option<Filter>,
+    group_by: Vec<Tag>,
+}
+
+impl List {
+    /// List distinct values of `tag`.
+    pub fn new(tag: Tag) -> Self {
+        List {
+            tag,
+            filter: None,
+            group_by: Vec::new(),
+        }
+    }
+
+    /// Filter the songs being considered using the given `filter`.
+    pub fn filter(mut self, filter: Filter) -> Self {
+        self.filter = Some(filter);
+        self
+    }
+
+    /// Group results by the given tag.
+    ///
+    /// This may be used multiple times, in which case the first group is the outermost one. Use
+    /// [`List::grouped`][res::List::grouped] on the response to get the nested values.
+    pub fn group_by(mut self, group_by: Tag) -> Self {
+        self.group_by.push(group_by);
+        self
+    }
+}
+
+impl Command for List {
+    type Response = res::List;
+
+    fn into_command(self) -> RawCommand {
+        let mut command = RawCommand::new("list").argument(self.tag);
+
+        if let Some(filter) = self.filter {
+            command.add_argument(filter).unwrap();
+        }
+
+        // MPD nests the group given last outermost
+        for group_by in self.group_by.into_iter().rev() {
+            command.add_argument("group").unwrap();
+            command.add_argument(group_by).unwrap();
+        }
+
+        command
+    }
+
+    fn min_protocol_version(&self) -> Option<(u32, u32, u32)> {
+        self.filter.as_ref().and_then(Filter::min_protocol_version)
+    }
+}
+
+/// `count` command.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Count {
+    filter: Filter,
+    group_by: Option<Tag>,
+}
+
+impl Count {
+    /// Count the songs matching `filter` and their total playtime.
+    pub fn new(filter: Filter) -> Self {
+        Self {
+            filter,
+            group_by: None,
+        }
+    }
+
+    /// Count separately for every value of the given tag.
+    pub fn group_by(mut self, group_by: Tag) -> Self {
+        self.group_by = Some(group_by);
+        self
+    }
+}
+
+impl Command for Count {
+    type Response = Vec<res::Count>;
+
+    fn into_command(self) -> RawCommand {
+        let mut command = RawCommand::new("count").argument(self.filter);
+
+        if let Some(group_by) = self.group_by {
+            command.add_argument("group").unwrap();
+            command.add_argument(group_by).unwrap();
+        }
+
+        command
+    }
+
+    fn min_protocol_version(&self) -> Option<(u32, u32, u32)> {
+        self.filter.min_protocol_version()
+    }
+}
+
+/// `rename` command.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RenamePlaylist {
+    from: String,
+    to: String,
+}
+
+impl RenamePlaylist {
+    /// Rename the playlist named `from` to `to`.
+    pub fn new(from: String, to: String) -> Self {
+        Self { from, to }
+    }
+}
+
+impl Command for RenamePlaylist {
+    type Response = res::Empty;
+
+    fn into_command(self) -> RawCommand {
+        RawCommand::new("rename")
+            .argument(self.from)
+            .argument(self.to)
+    }
+}
+
+/// `save` command.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SaveQueueAsPlaylist {
+    name: String,
+    mode: Option<SaveMode>,
+}
+
+impl SaveQueueAsPlaylist {
+    /// Save the queue as a new playlist with the given name.
+    ///
+    /// If the playlist already exists, this fails with [`ErrorCode::Exist`].
+    ///
+    /// [`ErrorCode::Exist`]: crate::raw::ErrorCode::Exist
+    pub fn new(name: String) -> Self {
+        Self { name, mode: None }
+    }
+
+    /// Explicitly specify how an existing playlist is treated.
+    ///
+    /// **NOTE**: Supported on protocol versions 0.24 and later.
+    pub fn mode(mut self, mode: SaveMode) -> Self {
+        self.mode = Some(mode);
+        self
+    }
+}
+
+impl Command for SaveQueueAsPlaylist {
+    type Response = res::Empty;
+
+    fn into_command(self) -> RawCommand {
+        let mut command = RawCommand::new("save").argument(self.name);
+
+        if let Some(mode) = self.mode {
+            let mode = match mode {
+                SaveMode::Create => "create",
+                SaveMode::Append => "append",
+                SaveMode::Replace => "replace",
+            };
+
+            command.add_argument(mode).unwrap();
+        }
+
+        command
+    }
+
+    fn min_protocol_version(&self) -> Option<(u32, u32, u32)> {
+        self.mode.map(|_| (0, 24, 0))
+    }
+}
+
+/// `load` command.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LoadPlaylist {
+    name: String,
+    range: Option<SongRange>,
+}
+
+impl LoadPlaylist {
+    /// Load the playlist with the given name into the queue.
+    pub fn name(name: String) -> Self {
+        Self { name, range: None }
+    }
+
+    /// Limit the loaded playlist to the given window.
+    pub fn range<R>(mut self, range: R) -> Self
+    where
+        R: RangeBounds<usize>,
+    {
+        self.range = Some(SongRange::new_usize(range));
+        self
+    }
+}
+
+impl Command for LoadPlaylist {
+    type Response = res::Empty;
+
+    fn into_command(self) -> RawCommand {
+        let mut command = RawCommand::new("load").argument(self.name);
+
+        if let Some(range) = self.range {
+            command.add_argument(range).unwrap();
+        }
+
+        command
+    }
+}
+
+/// `playlistadd` command.
+///
+/// If [`AddToPlaylist::at`] is not used, the song will be appended to the playlist.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct AddToPlaylist {
+    playlist: String,
+    song_url: String,
+    position: Option<SongPosition>,
+}
+
+impl AddToPlaylist {
+    /// Add `song_url` to `playlist`.
+    pub fn new(playlist: String, song_url: String) -> Self {
+        Self {
+            playlist,
+            song_url,
+            position: None,
+        }
+    }
+
+    /// Add the URI at the given position in the queue.
+    ///
+    /// **NOTE**: Supported on protocol versions 0.23.3 and later.
+    pub fn at<P: Into<SongPosition>>(mut self, position: P) -> Self {
+        self.position = Some(position.into());
+        self
+    }
+}
+
+impl Command for AddToPlaylist {
+    type Response = res::Empty;
+
+    fn into_command(self) -> RawCommand {
+        let mut command = RawCommand::new("playlistadd")
+            .argument(self.playlist)
+            .argument(self.song_url);
+
+        if let Some(pos) = self.position {
+            command.add_argument(pos).unwrap();
+        }
+
+        command
+    }
+
+    fn min_protocol_version(&self) -> Option<(u32, u32, u32)> {
+        self.position.map(|_| (0, 23, 3))
+    }
+}
+
+/// `playlistdelete` command.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RemoveFromPlaylist {
+    playlist: String,
+    target: PositionOrRange,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum PositionOrRange {
+    Position(usize),
+    Range(SongRange),
+}
+
+impl RemoveFromPlaylist {
+    /// Delete the song at `position` from `playlist`.
+    pub fn position(playlist: String, position: usize) -> Self {
+        RemoveFromPlaylist {
+            playlist,
+            target: PositionOrRange::Position(position),
+        }
+    }
+
+    /// Delete the specified range of songs from `playlist`.
+    pub fn range<R>(playlist: String, range: R) -> Self
+    where
+        R: RangeBounds<SongPosition>,
+    {
+        RemoveFromPlaylist {
+            playlist,
+            target: PositionOrRange::Range(SongRange::new(range)),
+        }
+    }
+}
+
+impl Command for RemoveFromPlaylist {
+    type Response = res::Empty;
+
+    fn into_command(self) -> RawCommand {
+        let command = RawCommand::new("playlistdelete").argument(self.playlist);
+
+        match self.target {
+            PositionOrRange::Position(p) => command.argument(p),
+            PositionOrRange::Range(r) => command.argument(r),
+        }
+    }
+}
+
+/// `playlistmove` command.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MoveInPlaylist {
+    playlist: String,
+    from: PositionOrRange,
+    to: usize,
+}
+
+impl MoveInPlaylist {
+    /// Move the song at `from` to `to` in the playlist named `playlist`.
+    pub fn new(playlist: String, from: usize, to: usize) -> Self {
+        Self {
+            playlist,
+            from: PositionOrRange::Position(from),
+            to,
+        }
+    }
+
+    /// Move the specified range of songs to `to` in the playlist named `playlist`.
+    ///
+    /// **NOTE**: Supported on protocol versions 0.24 and later.
+    pub fn range<R>(playlist: String, range: R, to: usize) -> Self
+    where
+        R: RangeBounds<SongPosition>,
+    {
+        Self {
+            playlist,
+            from: PositionOrRange::Range(SongRange::new(range)),
+            to,
+        }
+    }
+}
+
+impl Command for MoveInPlaylist {
+    type Response = res::Empty;
+
+    fn into_command(self) -> RawCommand {
+        let command = RawCommand::new("playlistmove").argument(self.playlist);
+
+        let command = match self.from {
+            PositionOrRange::Position(p) => command.argument(p),
+            PositionOrRange::Range(r) => command.argument(r),
+        };
+
+        command.argument(self.to)
+    }
+
+    fn min_protocol_version(&self) -> Option<(u32, u32, u32)> {
+        match self.from {
+            PositionOrRange::Position(_) => None,
+            PositionOrRange::Range(_) => Some((0, 24, 0)),
+        }
+    }
+}
+
+/// `update` and `rescan` commands.
+///
+/// Use [`Client::wait_for_update`][crate::Client::wait_for_update] to wait for the returned job
+/// to finish.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Update {
+    uri: Option<String>,
+    rescan: bool,
+}
+
+impl Update {
+    /// Update the entire music database.
+    pub fn all() -> Self {
+        Self {
+            uri: None,
+            rescan: false,
+        }
+    }
+
+    /// Update the given file or directory.
+    pub fn uri(uri: String) -> Self {
+        Self {
+            uri: Some(uri),
+            rescan: false,
+        }
+    }
+
+    /// Also rescan files which appear to be unmodified.
+    pub fn rescan(mut self) -> Self {
+        self.rescan = true;
+        self
+    }
+}
+
+impl Command for Update {
+    type Response = UpdateJob;
+
+    fn into_command(self) -> RawCommand {
+        let mut command = RawCommand::new(if self.rescan { "rescan" } else { "update" });
+
+        if let Some(uri) = self.uri {
+            command.add_argument(uri).unwrap();
+        }
+
+        command
+    }
+}
+
+/// `lsinfo` command.
+///
+/// Lists the songs, subdirectories and playlists in a directory of the database, without
+/// recursing.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ListInfo {
+    directory: String,
+}
+
+impl ListInfo {
+    /// List the contents of the root directory.
+    pub fn root() -> Self {
+        Self {
+            directory: String::new(),
+        }
+    }
+
+    /// List the contents of the given directory.
+    pub fn directory(directory: String) -> Self {
+        Self { directory }
+    }
+}
+
+impl Command for ListInfo {
+    type Response = Vec<res::DirectoryEntry>;
+
+    fn into_command(self) -> RawCommand {
+        let mut command = RawCommand::new("lsinfo");
+
+        if !self.directory.is_empty() {
+            command.add_argument(self.directory).unwrap();
+        }
+
+        command
+    }
+}
+
+/// `listfiles` command.
+///
+/// Lists all files and subdirectories in a directory of the music directory, including files
+/// which are not songs.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ListFiles {
+    directory: String,
+}
+
+impl ListFiles {
+    /// List the contents of the root directory.
+    pub fn root() -> Self {
+        Self {
+            directory: String::new(),
+        }
+    }
+
+    /// List the contents of the given directory.
+    pub fn directory(directory: String) -> Self {
+        Self { directory }
+    }
+}
+
+impl Command for ListFiles {
+    type Response = Vec<res::DirectoryEntry>;
+
+    fn into_command(self) -> RawCommand {
+        let mut command = RawCommand::new("listfiles");
+
+        if !self.directory.is_empty() {
+            command.add_argument(self.directory).unwrap();
+        }
+
+        command
+    }
+}
+
+/// `listallinfo` command.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ListAllIn {
+    directory: String,
+}
+
+impl ListAllIn {
+    /// List all songs in the library.
+    pub fn root() -> Self {
+        Self {
+            directory: String::new(),
+        }
+    }
+
+    /// List all songs beneath the given directory.
+    pub fn directory(directory: String) -> Self {
+        Self { directory }
+    }
+}
+
+impl Command for ListAllIn {
+    type Response = Vec<res::Song>;
+
+    fn into_command(self) -> RawCommand {
+        let mut command = RawCommand::new("listallinfo");
+
+        if !self.directory.is_empty() {
             command.add_argument(self.directory).unwrap();
         }
 
-        command
+        command
+    }
+}
+
+/// Set the response binary length limit, in bytes.
+///
+/// This can dramatically speed up operations like [loading album art][crate::Client::album_art],
+/// but may cause undesirable latency when using MPD over a slow connection.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SetBinaryLimit(pub usize);
+
+impl Command for SetBinaryLimit {
+    type Response = res::Empty;
+
+    fn into_command(self) -> RawCommand {
+        RawCommand::new("binarylimit").argument(self.0)
+    }
+}
+
+/// `albumart` command.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct AlbumArt {
+    uri: String,
+    offset: usize,
+}
+
+impl AlbumArt {
+    /// Get the separate file album art for the given URI.
+    pub fn new(uri: String) -> Self {
+        Self { uri, offset: 0 }
+    }
+
+    /// Load the resulting data starting from the given offset.
+    pub fn offset(self, offset: usize) -> Self {
+        Self { offset, ..self }
+    }
+}
+
+impl Command for AlbumArt {
+    type Response = Option<res::AlbumArt>;
+
+    fn into_command(self) -> RawCommand {
+        RawCommand::new("albumart")
+            .argument(self.uri)
+            .argument(self.offset)
+    }
+}
+
+/// `readpicture` command.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct AlbumArtEmbedded {
+    uri: String,
+    offset: usize,
+}
+
+impl AlbumArtEmbedded {
+    /// Get the separate file album art for the given URI.
+    pub fn new(uri: String) -> Self {
+        Self { uri, offset: 0 }
+    }
+
+    /// Load the resulting data starting from the given offset.
+    pub fn offset(self, offset: usize) -> Self {
+        Self { offset, ..self }
+    }
+}
+
+impl Command for AlbumArtEmbedded {
+    type Response = Option<res::AlbumArt>;
+
+    fn into_command(self) -> RawCommand {
+        RawCommand::new("readpicture")
+            .argument(self.uri)
+            .argument(self.offset)
+    }
+}
+
+/// Manage enabled tag types.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TagTypes(TagTypesAction);
+
+impl TagTypes {
+    /// Enable all tags.
+    pub fn enable_all() -> TagTypes {
+        TagTypes(TagTypesAction::EnableAll)
+    }
+
+    /// Disable all tags.
+    pub fn disable_all() -> TagTypes {
+        TagTypes(TagTypesAction::Clear)
+    }
+
+    /// Disable the given list of tags.
+    ///
+    /// # Panics
+    ///
+    /// Panics if called with an empty list of tags.
+    pub fn disable(tags: Vec<Tag>) -> TagTypes {
+        assert_ne!(tags.len(), 0, "The list of tags must not be empty");
+        TagTypes(TagTypesAction::Disable(tags))
+    }
+
+    /// Enable the given list of tags.
+    ///
+    /// # Panics
+    ///
+    /// Panics if called with an empty list of tags.
+    pub fn enable(tags: Vec<Tag>) -> TagTypes {
+        assert_ne!(tags.len(), 0, "The list of tags must not be empty");
+        TagTypes(TagTypesAction::Enable(tags))
+    }
+}
+
+impl Command for TagTypes {
+    type Response = res::Empty;
+
+    fn into_command(self) -> RawCommand {
+        let mut cmd = RawCommand::new("tagtypes");
+
+        match self.0 {
+            TagTypesAction::EnableAll => cmd.add_argument("all").unwrap(),
+            TagTypesAction::Clear => cmd.add_argument("clear").unwrap(),
+            TagTypesAction::Disable(tags) => {
+                cmd.add_argument("disable").unwrap();
+
+                for tag in tags {
+                    cmd.add_argument(tag).unwrap();
+                }
+            }
+            TagTypesAction::Enable(tags) => {
+                cmd.add_argument("enable").unwrap();
+
+                for tag in tags {
+                    cmd.add_argument(tag).unwrap();
+                }
+            }
+        }
+
+        cmd
+    }
+}
+
+/// Manage enabled protocol features, such as `hide_playlists_in_root`.
+///
+/// **NOTE**: Supported on protocol versions 0.24 and later.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ProtocolFeatures(ProtocolFeaturesAction);
+
+impl ProtocolFeatures {
+    /// Enable all features.
+    pub fn enable_all() -> ProtocolFeatures {
+        ProtocolFeatures(ProtocolFeaturesAction::EnableAll)
+    }
+
+    /// Disable all features.
+    pub fn disable_all() -> ProtocolFeatures {
+        ProtocolFeatures(ProtocolFeaturesAction::Clear)
+    }
+
+    /// Disable the given list of features.
+    ///
+    /// # Panics
+    ///
+    /// Panics if called with an empty list of features.
+    pub fn disable(features: Vec<String>) -> ProtocolFeatures {
+        assert_ne!(features.len(), 0, "The list of features must not be empty");
+        ProtocolFeatures(ProtocolFeaturesAction::Disable(features))
+    }
+
+    /// Enable the given list of features.
+    ///
+    /// # Panics
+    ///
+    /// Panics if called with an empty list of features.
+    pub fn enable(features: Vec<String>) -> ProtocolFeatures {
+        assert_ne!(features.len(), 0, "The list of features must not be empty");
+        ProtocolFeatures(ProtocolFeaturesAction::Enable(features))
+    }
+}
+
+impl Command for ProtocolFeatures {
+    type Response = res::Empty;
+
+    fn into_command(self) -> RawCommand {
+        let mut cmd = RawCommand::new("protocol");
+
+        match self.0 {
+            ProtocolFeaturesAction::EnableAll => cmd.add_argument("all").unwrap(),
+            ProtocolFeaturesAction::Clear => cmd.add_argument("clear").unwrap(),
+            ProtocolFeaturesAction::Disable(features) => {
+                cmd.add_argument("disable").unwrap();
+
+                for feature in features {
+                    cmd.add_argument(feature).unwrap();
+                }
+            }
+            ProtocolFeaturesAction::Enable(features) => {
+                cmd.add_argument("enable").unwrap();
+
+                for feature in features {
+                    cmd.add_argument(feature).unwrap();
+                }
+            }
+        }
+
+        cmd
+    }
+
+    fn min_protocol_version(&self) -> Option<(u32, u32, u32)> {
+        Some((0, 24, 0))
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum ProtocolFeaturesAction {
+    EnableAll,
+    Clear,
+    Disable(Vec<String>),
+    Enable(Vec<String>),
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum TagTypesAction {
+    EnableAll,
+    Clear,
+    Disable(Vec<Tag>),
+    Enable(Vec<Tag>),
+}
+
+/// `outputset` command.
+///
+/// Set a runtime attribute of an output. The available attributes depend on the output plugin,
+/// see [`Output::attributes`][res::Output::attributes].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SetOutputAttribute {
+    id: u32,
+    name: String,
+    value: String,
+}
+
+impl SetOutputAttribute {
+    /// Set the attribute `name` of the output with the given ID to `value`.
+    pub fn new(id: u32, name: String, value: String) -> Self {
+        Self { id, name, value }
+    }
+}
+
+impl Command for SetOutputAttribute {
+    type Response = res::Empty;
+
+    fn into_command(self) -> RawCommand {
+        RawCommand::new("outputset")
+            .argument(self.id)
+            .argument(self.name)
+            .argument(self.value)
+    }
+}
+
+/// `mount` command.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Mount {
+    path: String,
+    uri: String,
+}
+
+impl Mount {
+    /// Mount the storage with the given URI at `path`, relative to the root of the database.
+    pub fn new(path: String, uri: String) -> Self {
+        Self { path, uri }
+    }
+}
+
+impl Command for Mount {
+    type Response = res::Empty;
+
+    fn into_command(self) -> RawCommand {
+        RawCommand::new("mount")
+            .argument(self.path)
+            .argument(self.uri)
+    }
+}
+
+/// `sendmessage` command.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SendMessage {
+    channel: String,
+    message: String,
+}
+
+impl SendMessage {
+    /// Send `message` to all clients subscribed to `channel`.
+    pub fn new(channel: String, message: String) -> Self {
+        Self { channel, message }
+    }
+}
+
+impl Command for SendMessage {
+    type Response = res::Empty;
+
+    fn into_command(self) -> RawCommand {
+        RawCommand::new("sendmessage")
+            .argument(self.channel)
+            .argument(self.message)
+    }
+}
+
+/// `sticker get` command.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct StickerGet {
+    sticker_type: StickerType,
+    uri: String,
+    name: String,
+}
+
+impl StickerGet {
+    /// Get the sticker `name` of the object with the given type and URI.
+    ///
+    /// If the sticker does not exist, this fails with [`ErrorCode::NoExist`].
+    ///
+    /// [`ErrorCode::NoExist`]: crate::raw::ErrorCode::NoExist
+    pub fn new(sticker_type: StickerType, uri: String, name: String) -> Self {
+        Self {
+            sticker_type,
+            uri,
+            name,
+        }
+    }
+}
+
+impl Command for StickerGet {
+    type Response = res::Sticker;
+
+    fn into_command(self) -> RawCommand {
+        RawCommand::new("sticker")
+            .argument("get")
+            .argument(self.sticker_type)
+            .argument(self.uri)
+            .argument(self.name)
+    }
+
+    fn min_protocol_version(&self) -> Option<(u32, u32, u32)> {
+        self.sticker_type.min_protocol_version()
+    }
+}
+
+/// `sticker set` command.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct StickerSet {
+    sticker_type: StickerType,
+    uri: String,
+    name: String,
+    value: String,
+}
+
+impl StickerSet {
+    /// Set the sticker `name` of the object with the given type and URI to `value`.
+    pub fn new(sticker_type: StickerType, uri: String, name: String, value: String) -> Self {
+        Self {
+            sticker_type,
+            uri,
+            name,
+            value,
+        }
     }
 }
 
-/// Set the response binary length limit, in bytes.
-///
-/// This can dramatically speed up operations like [loading album art][crate::Client::album_art],
-/// but may cause undesirable latency when using MPD over a slow connection.
-#[derive(Clone, Copy, Debug, PartialEq, Eq)]
-pub struct SetBinaryLimit(pub usize);
-
-impl Command for SetBinaryLimit {
+impl Command for StickerSet {
     type Response = res::Empty;
 
     fn into_command(self) -> RawCommand {
-        RawCommand::new("binarylimit").argument(self.0.to_string())
+        RawCommand::new("sticker")
+            .argument("set")
+            .argument(self.sticker_type)
+            .argument(self.uri)
+            .argument(self.name)
+            .argument(self.value)
+    }
+
+    fn min_protocol_version(&self) -> Option<(u32, u32, u32)> {
+        self.sticker_type.min_protocol_version()
     }
 }
 
-/// `albumart` command.
+/// `sticker delete` command.
 #[derive(Clone, Debug, PartialEq, Eq)]
-pub struct AlbumArt {
+pub struct StickerDelete {
+    sticker_type: StickerType,
     uri: String,
-    offset: usize,
+    name: Option<String>,
 }
 
-impl AlbumArt {
-    /// Get the separate file album art for the given URI.
-    pub fn new(uri: String) -> Self {
-        Self { uri, offset: 0 }
+impl StickerDelete {
+    /// Delete the sticker `name` from the object with the given type and URI.
+    pub fn new(sticker_type: StickerType, uri: String, name: String) -> Self {
+        Self {
+            sticker_type,
+            uri,
+            name: Some(name),
+        }
     }
 
-    /// Load the resulting data starting from the given offset.
-    pub fn offset(self, offset: usize) -> Self {
-        Self { offset, ..self }
+    /// Delete all stickers from the object with the given type and URI.
+    pub fn all(sticker_type: StickerType, uri: String) -> Self {
+        Self {
+            sticker_type,
+            uri,
+            name: None,
+        }
     }
 }
 
-impl Command for AlbumArt {
-    type Response = Option<res::AlbumArt>;
+impl Command for StickerDelete {
+    type Response = res::Empty;
 
     fn into_command(self) -> RawCommand {
-        RawCommand::new("albumart")
-            .argument(self.uri)
-            .argument(self.offset.to_string())
+        let mut command = RawCommand::new("sticker")
+            .argument("delete")
+            .argument(self.sticker_type)
+            .argument(self.uri);
+
+        if let Some(name) = self.name {
+            command.add_argument(name).unwrap();
+        }
+
+        command
+    }
+
+    fn min_protocol_version(&self) -> Option<(u32, u32, u32)> {
+        self.sticker_type.min_protocol_version()
     }
 }
 
-/// `readpicture` command.
+/// `sticker list` command.
 #[derive(Clone, Debug, PartialEq, Eq)]
-pub struct AlbumArtEmbedded {
+pub struct StickerList {
+    sticker_type: StickerType,
     uri: String,
-    offset: usize,
 }
 
-impl AlbumArtEmbedded {
-    /// Get the separate file album art for the given URI.
-    pub fn new(uri: String) -> Self {
-        Self { uri, offset: 0 }
-    }
-
-    /// Load the resulting data starting from the given offset.
-    pub fn offset(self, offset: usize) -> Self {
-        Self { offset, ..self }
+impl StickerList {
+    /// List all stickers of the object with the given type and URI.
+    pub fn new(sticker_type: StickerType, uri: String) -> Self {
+        Self { sticker_type, uri }
     }
 }
 
-impl Command for AlbumArtEmbedded {
-    type Response = Option<res::AlbumArt>;
+impl Command for StickerList {
+    type Response = Vec<res::Sticker>;
 
     fn into_command(self) -> RawCommand {
-        RawCommand::new("readpicture")
+        RawCommand::new("sticker")
+            .argument("list")
+            .argument(self.sticker_type)
             .argument(self.uri)
-            .argument(self.offset.to_string())
+    }
+
+    fn min_protocol_version(&self) -> Option<(u32, u32, u32)> {
+        self.sticker_type.min_protocol_version()
     }
 }
 
-/// Manage enabled tag types.
+/// `sticker find` command.
 #[derive(Clone, Debug, PartialEq, Eq)]
-pub struct TagTypes(TagTypesAction);
+pub struct StickerFind {
+    sticker_type: StickerType,
+    uri: String,
+    name: String,
+    value: Option<(StickerOperator, String)>,
+    sort: Option<(StickerSort, bool)>,
+    window: Option<SongRange>,
+}
 
-impl TagTypes {
-    /// Enable all tags.
-    pub fn enable_all() -> TagTypes {
-        TagTypes(TagTypesAction::EnableAll)
+impl StickerFind {
+    /// Find all objects of the given type beneath `uri` which have a sticker named `name`.
+    ///
+    /// For songs, `uri` is a directory (use an empty string for the entire database).
+    pub fn new(sticker_type: StickerType, uri: String, name: String) -> Self {
+        Self {
+            sticker_type,
+            uri,
+            name,
+            value: None,
+            sort: None,
+            window: None,
+        }
     }
 
-    /// Disable all tags.
-    pub fn disable_all() -> TagTypes {
-        TagTypes(TagTypesAction::Clear)
+    /// Only find stickers whose value compares to `value` using the given operator.
+    pub fn value(mut self, operator: StickerOperator, value: String) -> Self {
+        self.value = Some((operator, value));
+        self
     }
 
-    /// Disable the given list of tags.
-    ///
-    /// # Panics
+    /// Sort the result in ascending order.
     ///
-    /// Panics if called with an empty list of tags.
-    pub fn disable(tags: Vec<Tag>) -> TagTypes {
-        assert_ne!(tags.len(), 0, "The list of tags must not be empty");
-        TagTypes(TagTypesAction::Disable(tags))
+    /// **NOTE**: Supported on protocol versions 0.24 and later.
+    pub fn sort(mut self, sort: StickerSort) -> Self {
+        self.sort = Some((sort, false));
+        self
     }
 
-    /// Enable the given list of tags.
+    /// Sort the result in descending order.
     ///
-    /// # Panics
+    /// **NOTE**: Supported on protocol versions 0.24 and later.
+    pub fn sort_descending(mut self, sort: StickerSort) -> Self {
+        self.sort = Some((sort, true));
+        self
+    }
+
+    /// Limit the result to the given window.
     ///
-    /// Panics if called with an empty list of tags.
-    pub fn enable(tags: Vec<Tag>) -> TagTypes {
-        assert_ne!(tags.len(), 0, "The list of tags must not be empty");
-        TagTypes(TagTypesAction::Enable(tags))
+    /// **NOTE**: Supported on protocol versions 0.24 and later.
+    pub fn window<R>(mut self, window: R) -> Self
+    where
+        R: RangeBounds<usize>,
+    {
+        self.window = Some(SongRange::new_usize(window));
+        self
     }
 }
 
-impl Command for TagTypes {
-    type Response = res::Empty;
+impl Command for StickerFind {
+    type Response = Vec<res::StickerMatch>;
 
     fn into_command(self) -> RawCommand {
-        let mut cmd = RawCommand::new("tagtypes");
+        let mut command = RawCommand::new("sticker")
+            .argument("find")
+            .argument(self.sticker_type)
+            .argument(self.uri)
+            .argument(self.name);
 
-        match self.0 {
-            TagTypesAction::EnableAll => cmd.add_argument("all").unwrap(),
-            TagTypesAction::Clear => cmd.add_argument("clear").unwrap(),
-            TagTypesAction::Disable(tags) => {
-                cmd.add_argument("disable").unwrap();
+        if let Some((operator, value)) = self.value {
+            command.add_argument(operator).unwrap();
+            command.add_argument(value).unwrap();
+        }
 
-                for tag in tags {
-                    cmd.add_argument(tag).unwrap();
-                }
-            }
-            TagTypesAction::Enable(tags) => {
-                cmd.add_argument("enable").unwrap();
+        if let Some((sort, descending)) = self.sort {
+            let sort = match sort {
+                StickerSort::Uri => "uri",
+                StickerSort::Value => "value",
+                StickerSort::ValueInt => "value_int",
+            };
 
-                for tag in tags {
-                    cmd.add_argument(tag).unwrap();
-                }
-            }
+            command.add_argument("sort").unwrap();
+            command
+                .add_argument(if descending {
+                    Cow::Owned(format!("-{}", sort))
+                } else {
+                    Cow::Borrowed(sort)
+                })
+                .unwrap();
         }
 
-        cmd
+        if let Some(window) = self.window {
+            command.add_argument("window").unwrap();
+            command.add_argument(window).unwrap();
+        }
+
+        command
+    }
+
+    fn min_protocol_version(&self) -> Option<(u32, u32, u32)> {
+        let extended = match (&self.value, &self.sort, &self.window) {
+            (_, Some(_), _) | (_, _, Some(_)) => Some((0, 24, 0)),
+            (Some((operator, _)), None, None) => operator.min_protocol_version(),
+            (None, None, None) => None,
+        };
+
+        self.sticker_type.min_protocol_version().max(extended)
     }
 }
 
+/// `stickernamestypes` command.
+///
+/// **NOTE**: Supported on protocol versions 0.24 and later.
 #[derive(Clone, Debug, PartialEq, Eq)]
-enum TagTypesAction {
-    EnableAll,
-    Clear,
-    Disable(Vec<Tag>),
-    Enable(Vec<Tag>),
+pub struct StickerNamesTypes(Option<StickerType>);
+
+impl StickerNamesTypes {
+    /// List the names of all stickers, along with the types of objects they are attached to.
+    pub fn all() -> Self {
+        Self(None)
+    }
+
+    /// Only list stickers attached to objects of the given type.
+    pub fn sticker_type(sticker_type: StickerType) -> Self {
+        Self(Some(sticker_type))
+    }
+}
+
+impl Command for StickerNamesTypes {
+    type Response = Vec<res::StickerNameTypes>;
+
+    fn into_command(self) -> RawCommand {
+        let mut command = RawCommand::new("stickernamestypes");
+
+        if let Some(sticker_type) = self.0 {
+            command.add_argument(sticker_type).unwrap();
+        }
+
+        command
+    }
+
+    fn min_protocol_version(&self) -> Option<(u32, u32, u32)> {
+        Some((0, 24, 0))
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::filter::Operator;
 
     #[test]
     fn range_arg() {
@@ -968,6 +2590,34 @@ mod tests {
         );
     }
 
+    #[test]
+    fn command_change_volume() {
+        assert_eq!(
+            ChangeVolume(5).into_command(),
+            RawCommand::new("volume").argument("+5")
+        );
+        assert_eq!(
+            ChangeVolume(-10).into_command(),
+            RawCommand::new("volume").argument("-10")
+        );
+    }
+
+    #[test]
+    fn command_single_and_consume() {
+        assert_eq!(
+            SetSingle(SingleMode::Oneshot).into_command(),
+            RawCommand::new("single").argument("oneshot")
+        );
+        assert_eq!(
+            SetConsume(SingleMode::Enabled).into_command(),
+            RawCommand::new("consume").argument("1")
+        );
+        assert_eq!(
+            SetConsume(SingleMode::Oneshot).into_command(),
+            RawCommand::new("consume").argument("oneshot")
+        );
+    }
+
     #[test]
     fn command_seek_to() {
         let duration = Duration::from_secs(2);
@@ -1023,22 +2673,163 @@ mod tests {
         let uri = String::from("foo/bar.mp3");
 
         assert_eq!(
-            Add::uri(uri.clone()).into_command(),
-            RawCommand::new("addid").argument(uri.clone())
+            Add::uri(uri.clone()).into_command(),
+            RawCommand::new("addid").argument(uri.clone())
+        );
+        assert_eq!(
+            Add::uri(uri.clone()).at(5).into_command(),
+            RawCommand::new("addid").argument(uri.clone()).argument("5")
+        );
+        assert_eq!(
+            Add::uri(uri.clone()).before_current(5).into_command(),
+            RawCommand::new("addid")
+                .argument(uri.clone())
+                .argument("-5")
+        );
+        assert_eq!(
+            Add::uri(uri.clone()).after_current(5).into_command(),
+            RawCommand::new("addid").argument(uri).argument("+5")
+        );
+    }
+
+    #[test]
+    fn command_add_recursive() {
+        let uri = String::from("foo");
+
+        assert_eq!(
+            AddRecursive::uri(uri.clone()).into_command(),
+            RawCommand::new("add").argument(uri.clone())
+        );
+        assert_eq!(
+            AddRecursive::uri(uri.clone()).at(5).into_command(),
+            RawCommand::new("add").argument(uri.clone()).argument("5")
+        );
+        assert_eq!(
+            AddRecursive::uri(uri.clone())
+                .after_current(0)
+                .into_command(),
+            RawCommand::new("add").argument(uri).argument("+0")
+        );
+    }
+
+    #[test]
+    fn command_set_priority() {
+        assert_eq!(
+            SetPriority::id(SongId(7), 100).into_command(),
+            RawCommand::new("prioid").argument("100").argument("7")
+        );
+        assert_eq!(
+            SetPriority::position(SongPosition(2), 255).into_command(),
+            RawCommand::new("prio").argument("255").argument("2:3")
+        );
+        assert_eq!(
+            SetPriority::range(SongPosition(2).., 1).into_command(),
+            RawCommand::new("prio").argument("1").argument("2:")
+        );
+    }
+
+    #[test]
+    fn command_set_song_range() {
+        let from = Duration::from_secs_f64(1.5);
+        let to = Duration::from_secs(10);
+
+        assert_eq!(
+            SetSongRange::new(SongId(2), from..to).into_command(),
+            RawCommand::new("rangeid")
+                .argument("2")
+                .argument("1.500:10.000")
+        );
+        assert_eq!(
+            SetSongRange::new(SongId(2), from..).into_command(),
+            RawCommand::new("rangeid").argument("2").argument("1.500:")
+        );
+        assert_eq!(
+            SetSongRange::new(SongId(2), ..=to).into_command(),
+            RawCommand::new("rangeid").argument("2").argument(":10.000")
+        );
+        assert_eq!(
+            SetSongRange::clear(SongId(2)).into_command(),
+            RawCommand::new("rangeid").argument("2").argument(":")
+        );
+    }
+
+    #[test]
+    fn command_replay_gain_mode() {
+        assert_eq!(
+            SetReplayGainMode(ReplayGainMode::Auto).into_command(),
+            RawCommand::new("replay_gain_mode").argument("auto")
+        );
+    }
+
+    #[test]
+    fn command_song_tags() {
+        assert_eq!(
+            AddTagToSong::new(SongId(2), Tag::Artist, String::from("foo")).into_command(),
+            RawCommand::new("addtagid")
+                .argument("2")
+                .argument("Artist")
+                .argument("foo")
+        );
+        assert_eq!(
+            ClearTagsOfSong::all(SongId(2)).into_command(),
+            RawCommand::new("cleartagid").argument("2")
+        );
+        assert_eq!(
+            ClearTagsOfSong::tag(SongId(2), Tag::Title).into_command(),
+            RawCommand::new("cleartagid")
+                .argument("2")
+                .argument("Title")
+        );
+    }
+
+    #[test]
+    fn command_queue_changes() {
+        assert_eq!(
+            QueueChanges::new(12).into_command(),
+            RawCommand::new("plchanges").argument("12")
+        );
+        assert_eq!(
+            QueueChanges::new(12)
+                .range(SongPosition(2)..SongPosition(5))
+                .into_command(),
+            RawCommand::new("plchanges").argument("12").argument("2:5")
+        );
+        assert_eq!(
+            QueueChangesIds::new(12)
+                .range(SongPosition(2)..)
+                .into_command(),
+            RawCommand::new("plchangesposid")
+                .argument("12")
+                .argument("2:")
+        );
+    }
+
+    #[test]
+    fn command_queue_song_by_id() {
+        assert_eq!(
+            QueueSongById(SongId(7)).into_command(),
+            RawCommand::new("playlistid").argument("7")
         );
+    }
+
+    #[test]
+    fn command_shuffle() {
+        assert_eq!(Shuffle::all().into_command(), RawCommand::new("shuffle"));
         assert_eq!(
-            Add::uri(uri.clone()).at(5).into_command(),
-            RawCommand::new("addid").argument(uri.clone()).argument("5")
+            Shuffle::range(SongPosition(2)..SongPosition(5)).into_command(),
+            RawCommand::new("shuffle").argument("2:5")
         );
         assert_eq!(
-            Add::uri(uri.clone()).before_current(5).into_command(),
-            RawCommand::new("addid")
-                .argument(uri.clone())
-                .argument("-5")
+            Shuffle::range(SongPosition(10)..).into_command(),
+            RawCommand::new("shuffle").argument("10:")
         );
+    }
+
+    #[test]
+    fn command_queue_range() {
         assert_eq!(
-            Add::uri(uri.clone()).after_current(5).into_command(),
-            RawCommand::new("addid").argument(uri).argument("+5")
+            QueueRange::new(SongPosition(2)..=SongPosition(3)).into_command(),
+            RawCommand::new("playlistinfo").argument("2:4")
         );
     }
 
@@ -1102,6 +2893,117 @@ mod tests {
         );
     }
 
+    #[test]
+    fn command_find_in_queue() {
+        let filter = Filter::tag(Tag::Artist, "Foo");
+
+        assert_eq!(
+            FindInQueue(filter.clone()).into_command(),
+            RawCommand::new("playlistfind").argument(filter.clone())
+        );
+        assert_eq!(
+            SearchInQueue(filter.clone()).into_command(),
+            RawCommand::new("playlistsearch").argument(filter)
+        );
+    }
+
+    #[test]
+    fn command_min_protocol_version() {
+        assert_eq!(Ping.min_protocol_version(), None);
+        assert_eq!(GetVolume.min_protocol_version(), Some((0, 23, 0)));
+        assert_eq!(ChangeVolume(5).min_protocol_version(), None);
+        assert_eq!(SetConsume(SingleMode::Enabled).min_protocol_version(), None);
+        assert_eq!(
+            SetConsume(SingleMode::Oneshot).min_protocol_version(),
+            Some((0, 24, 0))
+        );
+
+        assert_eq!(
+            Add::uri(String::from("foo")).at(1).min_protocol_version(),
+            None
+        );
+        assert_eq!(
+            Add::uri(String::from("foo"))
+                .after_current(1)
+                .min_protocol_version(),
+            Some((0, 23, 0))
+        );
+
+        let filter = Filter::tag(Tag::Artist, "foo");
+        assert_eq!(Find::new(filter.clone()).min_protocol_version(), None);
+        assert_eq!(
+            FindAdd::new(filter.clone()).at(1).min_protocol_version(),
+            Some((0, 23, 4))
+        );
+
+        let filter = filter.and(Filter::new(
+            Tag::Album,
+            Operator::ContainCaseInsensitive,
+            "bar",
+        ));
+        assert_eq!(
+            Find::new(filter.clone()).min_protocol_version(),
+            Some((0, 24, 0))
+        );
+        assert_eq!(
+            List::new(Tag::Album)
+                .filter(!filter.clone())
+                .min_protocol_version(),
+            Some((0, 24, 0))
+        );
+
+        assert_eq!(FindInQueue(filter).min_protocol_version(), Some((0, 24, 0)));
+        assert_eq!(
+            SearchInQueue(Filter::tag(Tag::Artist, "foo")).min_protocol_version(),
+            None
+        );
+
+        assert_eq!(
+            SaveQueueAsPlaylist::new(String::from("foo")).min_protocol_version(),
+            None
+        );
+        assert_eq!(
+            SaveQueueAsPlaylist::new(String::from("foo"))
+                .mode(SaveMode::Append)
+                .min_protocol_version(),
+            Some((0, 24, 0))
+        );
+        let add = AddToPlaylist::new(String::from("foo"), String::from("bar"));
+        assert_eq!(add.clone().min_protocol_version(), None);
+        assert_eq!(add.at(1).min_protocol_version(), Some((0, 23, 3)));
+        assert_eq!(
+            MoveInPlaylist::new(String::from("foo"), 1, 2).min_protocol_version(),
+            None
+        );
+        assert_eq!(
+            MoveInPlaylist::range(String::from("foo"), SongPosition(1)..SongPosition(3), 5)
+                .min_protocol_version(),
+            Some((0, 24, 0))
+        );
+        assert_eq!(
+            EnabledProtocolFeatures.min_protocol_version(),
+            Some((0, 24, 0))
+        );
+
+        let find = StickerFind::new(StickerType::Song, String::new(), String::from("rating"));
+        assert_eq!(find.clone().min_protocol_version(), None);
+        assert_eq!(find.window(..10).min_protocol_version(), Some((0, 24, 0)));
+        assert_eq!(
+            StickerGet::new(
+                StickerType::Playlist,
+                String::from("foo"),
+                String::from("bar")
+            )
+            .min_protocol_version(),
+            Some((0, 24, 0))
+        );
+    }
+
+    #[test]
+    fn command_kill() {
+        assert_eq!(Kill.into_command(), RawCommand::new("kill"));
+    }
+
     #[test]
     fn command_find() {
         let filter = Filter::tag(Tag::Artist, "Foo");
@@ -1133,6 +3035,98 @@ mod tests {
         );
     }
 
+    #[test]
+    fn command_search() {
+        let filter = Filter::tag(Tag::Artist, "foo");
+
+        assert_eq!(
+            Search::new(filter.clone()).into_command(),
+            RawCommand::new("search").argument(filter.clone())
+        );
+
+        assert_eq!(
+            Search::new(filter.clone())
+                .sort_descending(Tag::Date)
+                .window(..10)
+                .into_command(),
+            RawCommand::new("search")
+                .argument(filter.clone())
+                .argument("sort")
+                .argument("-Date")
+                .argument("window")
+                .argument("0:10")
+        );
+
+        assert_eq!(
+            Find::new(filter.clone())
+                .sort_descending(Tag::Other("LastModified".into()))
+                .into_command(),
+            RawCommand::new("find")
+                .argument(filter)
+                .argument("sort")
+                .argument("-LastModified")
+        );
+    }
+
+    #[test]
+    fn command_find_and_search_add() {
+        let filter = Filter::tag(Tag::Album, "foo");
+
+        assert_eq!(
+            FindAdd::new(filter.clone()).into_command(),
+            RawCommand::new("findadd").argument(filter.clone())
+        );
+
+        assert_eq!(
+            SearchAdd::new(filter.clone())
+                .sort(Tag::Track)
+                .after_current(0)
+                .into_command(),
+            RawCommand::new("searchadd")
+                .argument(filter.clone())
+                .argument("sort")
+                .argument("Track")
+                .argument("position")
+                .argument("+0")
+        );
+
+        assert_eq!(
+            SearchAddToPlaylist::new(String::from("bar"), filter.clone())
+                .window(..5)
+                .at(2)
+                .into_command(),
+            RawCommand::new("searchaddpl")
+                .argument("bar")
+                .argument(filter)
+                .argument("window")
+                .argument("0:5")
+                .argument("position")
+                .argument("2")
+        );
+    }
+
+    #[test]
+    fn command_search_playlist() {
+        let filter = Filter::tag(Tag::Artist, "foo");
+
+        assert_eq!(
+            SearchPlaylist::new(String::from("bar"), filter.clone()).into_command(),
+            RawCommand::new("searchplaylist")
+                .argument("bar")
+                .argument(filter.clone())
+        );
+
+        assert_eq!(
+            SearchPlaylist::new(String::from("bar"), filter.clone())
+                .window(..5)
+                .into_command(),
+            RawCommand::new("searchplaylist")
+                .argument("bar")
+                .argument(filter)
+                .argument("0:5")
+        );
+    }
+
     #[test]
     fn command_list() {
         assert_eq!(
@@ -1158,6 +3152,68 @@ mod tests {
                 .argument("group")
                 .argument("AlbumArtist")
         );
+
+        assert_eq!(
+            List::new(Tag::Album)
+                .group_by(Tag::AlbumArtist)
+                .group_by(Tag::Date)
+                .into_command(),
+            RawCommand::new("list")
+                .argument("Album")
+                .argument("group")
+                .argument("Date")
+                .argument("group")
+                .argument("AlbumArtist")
+        );
+    }
+
+    #[test]
+    fn command_count() {
+        let filter = Filter::tag(Tag::Album, "Foo");
+
+        assert_eq!(
+            Count::new(filter.clone()).into_command(),
+            RawCommand::new("count").argument(filter.clone())
+        );
+        assert_eq!(
+            Count::new(filter.clone())
+                .group_by(Tag::Artist)
+                .into_command(),
+            RawCommand::new("count")
+                .argument(filter)
+                .argument("group")
+                .argument("Artist")
+        );
+    }
+
+    #[test]
+    fn command_update() {
+        assert_eq!(Update::all().into_command(), RawCommand::new("update"));
+        assert_eq!(
+            Update::uri(String::from("foo")).into_command(),
+            RawCommand::new("update").argument("foo")
+        );
+        assert_eq!(
+            Update::uri(String::from("foo")).rescan().into_command(),
+            RawCommand::new("rescan").argument("foo")
+        );
+    }
+
+    #[test]
+    fn command_lsinfo_and_listfiles() {
+        assert_eq!(ListInfo::root().into_command(), RawCommand::new("lsinfo"));
+        assert_eq!(
+            ListFiles::directory(String::from("foo")).into_command(),
+            RawCommand::new("listfiles").argument("foo")
+        );
+    }
+
+    #[test]
+    fn command_readcomments() {
+        assert_eq!(
+            ReadComments(String::from("foo.flac")).into_command(),
+            RawCommand::new("readcomments").argument("foo.flac")
+        );
     }
 
     #[test]
@@ -1191,6 +3247,181 @@ mod tests {
         );
     }
 
+    #[test]
+    fn command_save() {
+        assert_eq!(
+            SaveQueueAsPlaylist::new(String::from("foo")).into_command(),
+            RawCommand::new("save").argument("foo")
+        );
+        assert_eq!(
+            SaveQueueAsPlaylist::new(String::from("foo"))
+                .mode(SaveMode::Append)
+                .into_command(),
+            RawCommand::new("save").argument("foo").argument("append")
+        );
+        assert_eq!(
+            SaveQueueAsPlaylist::new(String::from("foo"))
+                .mode(SaveMode::Replace)
+                .into_command(),
+            RawCommand::new("save").argument("foo").argument("replace")
+        );
+    }
+
+    #[test]
+    fn command_playlistmove() {
+        assert_eq!(
+            MoveInPlaylist::new(String::from("foo"), 2, 5).into_command(),
+            RawCommand::new("playlistmove")
+                .argument("foo")
+                .argument("2")
+                .argument("5"),
+        );
+
+        assert_eq!(
+            MoveInPlaylist::range(String::from("foo"), SongPosition(3)..SongPosition(6), 0)
+                .into_command(),
+            RawCommand::new("playlistmove")
+                .argument("foo")
+                .argument("3:6")
+                .argument("0"),
+        );
+    }
+
+    #[test]
+    fn command_outputset() {
+        assert_eq!(
+            SetOutputAttribute::new(1, String::from("dop"), String::from("1")).into_command(),
+            RawCommand::new("outputset")
+                .argument("1")
+                .argument("dop")
+                .argument("1")
+        );
+    }
+
+    #[test]
+    fn command_mount() {
+        assert_eq!(
+            Mount::new(String::from("nas"), String::from("nfs://host/music")).into_command(),
+            RawCommand::new("mount")
+                .argument("nas")
+                .argument("nfs://host/music")
+        );
+    }
+
+    #[test]
+    fn command_sendmessage() {
+        assert_eq!(
+            SendMessage::new(String::from("foo"), String::from("hello world")).into_command(),
+            RawCommand::new("sendmessage")
+                .argument("foo")
+                .argument("hello world")
+        );
+    }
+
+    #[test]
+    fn command_stickers() {
+        let uri = String::from("foo.flac");
+        let name = String::from("rating");
+
+        assert_eq!(
+            StickerGet::new(StickerType::Song, uri.clone(), name.clone()).into_command(),
+            RawCommand::new("sticker")
+                .argument("get")
+                .argument("song")
+                .argument(uri.clone())
+                .argument(name.clone())
+        );
+        assert_eq!(
+            StickerSet::new(
+                StickerType::Song,
+                uri.clone(),
+                name.clone(),
+                String::from("8")
+            )
+            .into_command(),
+            RawCommand::new("sticker")
+                .argument("set")
+                .argument("song")
+                .argument(uri.clone())
+                .argument(name.clone())
+                .argument("8")
+        );
+        assert_eq!(
+            StickerDelete::all(StickerType::Playlist, String::from("foo")).into_command(),
+            RawCommand::new("sticker")
+                .argument("delete")
+                .argument("playlist")
+                .argument("foo")
+        );
+        assert_eq!(
+            StickerList::new(StickerType::Tag(Tag::Album), String::from("Foo")).into_command(),
+            RawCommand::new("sticker")
+                .argument("list")
+                .argument("Album")
+                .argument("Foo")
+        );
+        assert_eq!(
+            StickerFind::new(StickerType::Song, String::new(), name.clone()).into_command(),
+            RawCommand::new("sticker")
+                .argument("find")
+                .argument("song")
+                .argument("")
+                .argument(name)
+        );
+    }
+
+    #[test]
+    fn command_sticker_find() {
+        let name = String::from("rating");
+
+        assert_eq!(
+            StickerFind::new(StickerType::Song, String::from("foo"), name.clone())
+                .value(StickerOperator::GreaterThanInt, String::from("5"))
+                .sort_descending(StickerSort::ValueInt)
+                .window(..10)
+                .into_command(),
+            RawCommand::new("sticker")
+                .argument("find")
+                .argument("song")
+                .argument("foo")
+                .argument(name.clone())
+                .argument("gt")
+                .argument("5")
+                .argument("sort")
+                .argument("-value_int")
+                .argument("window")
+                .argument("0:10")
+        );
+
+        assert_eq!(
+            StickerFind::new(StickerType::Song, String::new(), name.clone())
+                .value(StickerOperator::Equal, String::from("10"))
+                .sort(StickerSort::Uri)
+                .into_command(),
+            RawCommand::new("sticker")
+                .argument("find")
+                .argument("song")
+                .argument("")
+                .argument(name)
+                .argument("=")
+                .argument("10")
+                .argument("sort")
+                .argument("uri")
+        );
+    }
+
+    #[test]
+    fn command_stickernamestypes() {
+        assert_eq!(
+            StickerNamesTypes::all().into_command(),
+            RawCommand::new("stickernamestypes")
+        );
+        assert_eq!(
+            StickerNamesTypes::sticker_type(StickerType::Song).into_command(),
+            RawCommand::new("stickernamestypes").argument("song")
+        );
+    }
+
     #[test]
     fn command_tagtypes() {
         assert_eq!(
@@ -1220,6 +3451,43 @@ mod tests {
         );
     }
 
+    #[test]
+    fn command_protocol_features() {
+        assert_eq!(
+            EnabledProtocolFeatures.into_command(),
+            RawCommand::new("protocol")
+        );
+
+        assert_eq!(
+            AvailableProtocolFeatures.into_command(),
+            RawCommand::new("protocol").argument("available")
+        );
+
+        assert_eq!(
+            ProtocolFeatures::enable_all().into_command(),
+            RawCommand::new("protocol").argument("all"),
+        );
+
+        assert_eq!(
+            ProtocolFeatures::disable_all().into_command(),
+            RawCommand::new("protocol").argument("clear"),
+        );
+
+        assert_eq!(
+            ProtocolFeatures::enable(vec![String::from("hide_playlists_in_root")]).into_command(),
+            RawCommand::new("protocol")
+                .argument("enable")
+                .argument("hide_playlists_in_root")
+        );
+
+        assert_eq!(
+            ProtocolFeatures::disable(vec![String::from("hide_playlists_in_root")]).into_command(),
+            RawCommand::new("protocol")
+                .argument("disable")
+                .argument("hide_playlists_in_root")
+        );
+    }
+
     #[test]
     fn command_enabled_tagtypes() {
         assert_eq!(EnabledTagTypes.into_command(), RawCommand::new("tagtypes"));
@@ -12,6 +12,7 @@ pub mod definitions;
 pub mod responses;
 
 mod command_list;
+mod queue_diff;
 
 use std::borrow::Cow;
 use std::time::Duration;
@@ -19,13 +20,16 @@ use std::time::Duration;
 use mpd_protocol::command::Argument;
 
 use crate::raw::RawCommand;
+use crate::tag::Tag;
 use responses::Response;
 
 pub use command_list::CommandList;
 pub use definitions::*;
+pub use queue_diff::QueueEdit;
 
 /// Stable identifier of a song in the queue.
 #[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SongId(pub u64);
 
 impl From<u64> for SongId {
@@ -36,7 +40,7 @@ impl From<u64> for SongId {
 
 impl Argument for SongId {
     fn render(self) -> Cow<'static, str> {
-        Cow::Owned(self.0.to_string())
+        self.0.render()
     }
 }
 
@@ -44,6 +48,7 @@ impl Argument for SongId {
 ///
 /// This will change when the queue is modified.
 #[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SongPosition(pub usize);
 
 impl From<usize> for SongPosition {
@@ -54,10 +59,17 @@ impl From<usize> for SongPosition {
 
 impl Argument for SongPosition {
     fn render(self) -> Cow<'static, str> {
-        Cow::Owned(self.0.to_string())
+        self.0.render()
     }
 }
 
+/// Identifier of a database update job, as returned by the [`update`][Update] command.
+///
+/// Job IDs increase for every new job (until they wrap around).
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct UpdateJob(pub u64);
+
 /// Possible ways to seek in the current song.
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum SeekMode {
@@ -69,8 +81,9 @@ pub enum SeekMode {
     Absolute(Duration),
 }
 
-/// Possible `single` modes.
+/// Possible `single` and `consume` modes.
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[allow(missing_docs)]
 pub enum SingleMode {
     Enabled,
@@ -78,6 +91,157 @@ pub enum SingleMode {
     Oneshot,
 }
 
+impl Argument for SingleMode {
+    fn render(self) -> Cow<'static, str> {
+        Cow::Borrowed(match self {
+            SingleMode::Disabled => "0",
+            SingleMode::Enabled => "1",
+            SingleMode::Oneshot => "oneshot",
+        })
+    }
+}
+
+/// Possible [ReplayGain](https://en.wikipedia.org/wiki/ReplayGain) modes.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ReplayGainMode {
+    /// ReplayGain is disabled.
+    Off,
+    /// Use the track gain.
+    Track,
+    /// Use the album gain.
+    Album,
+    /// Use the album gain when playing in order, and the track gain otherwise.
+    Auto,
+}
+
+impl Argument for ReplayGainMode {
+    fn render(self) -> Cow<'static, str> {
+        Cow::Borrowed(match self {
+            ReplayGainMode::Off => "off",
+            ReplayGainMode::Track => "track",
+            ReplayGainMode::Album => "album",
+            ReplayGainMode::Auto => "auto",
+        })
+    }
+}
+
+/// Possible ways to [save][SaveQueueAsPlaylist] the queue to a stored playlist.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SaveMode {
+    /// Create a new playlist. Fails with [`ErrorCode::Exist`] if the playlist already exists.
+    ///
+    /// [`ErrorCode::Exist`]: crate::raw::ErrorCode::Exist
+    Create,
+    /// Append the queue to an existing playlist.
+    Append,
+    /// Replace the contents of an existing playlist.
+    Replace,
+}
+
+/// Types of objects [stickers][StickerGet] can be attached to.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum StickerType {
+    /// Songs, identified by their URI.
+    Song,
+    /// Stored playlists, identified by their name.
+    ///
+    /// **NOTE**: Supported on protocol versions 0.24 and later.
+    Playlist,
+    /// Values of the given tag, e.g. the name of an album.
+    ///
+    /// **NOTE**: Supported on protocol versions 0.24 and later.
+    Tag(Tag),
+}
+
+impl StickerType {
+    fn min_protocol_version(&self) -> Option<(u32, u32, u32)> {
+        match self {
+            StickerType::Song => None,
+            StickerType::Playlist | StickerType::Tag(_) => Some((0, 24, 0)),
+        }
+    }
+}
+
+impl Argument for StickerType {
+    fn render(self) -> Cow<'static, str> {
+        match self {
+            StickerType::Song => Cow::Borrowed("song"),
+            StickerType::Playlist => Cow::Borrowed("playlist"),
+            StickerType::Tag(tag) => tag.render(),
+        }
+    }
+}
+
+/// Operators for comparing sticker values in [`StickerFind`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum StickerOperator {
+    /// String equality (`=`).
+    Equal,
+    /// String comparison (`<`).
+    LessThan,
+    /// String comparison (`>`).
+    GreaterThan,
+    /// Integer equality (`eq`).
+    ///
+    /// **NOTE**: Supported on protocol versions 0.24 and later.
+    EqualInt,
+    /// Integer comparison (`lt`).
+    ///
+    /// **NOTE**: Supported on protocol versions 0.24 and later.
+    LessThanInt,
+    /// Integer comparison (`gt`).
+    ///
+    /// **NOTE**: Supported on protocol versions 0.24 and later.
+    GreaterThanInt,
+    /// Substring match (`contains`).
+    ///
+    /// **NOTE**: Supported on protocol versions 0.24 and later.
+    Contains,
+    /// Prefix match (`starts_with`).
+    ///
+    /// **NOTE**: Supported on protocol versions 0.24 and later.
+    StartsWith,
+}
+
+impl StickerOperator {
+    fn min_protocol_version(&self) -> Option<(u32, u32, u32)> {
+        match self {
+            StickerOperator::Equal | StickerOperator::LessThan | StickerOperator::GreaterThan => {
+                None
+            }
+            _ => Some((0, 24, 0)),
+        }
+    }
+}
+
+impl Argument for StickerOperator {
+    fn render(self) -> Cow<'static, str> {
+        Cow::Borrowed(match self {
+            StickerOperator::Equal => "=",
+            StickerOperator::LessThan => "<",
+            StickerOperator::GreaterThan => ">",
+            StickerOperator::EqualInt => "eq",
+            StickerOperator::LessThanInt => "lt",
+            StickerOperator::GreaterThanInt => "gt",
+            StickerOperator::Contains => "contains",
+            StickerOperator::StartsWith => "starts_with",
+        })
+    }
+}
+
+/// Possible ways to sort the results of [`StickerFind`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum StickerSort {
+    /// By the URI of the object.
+    Uri,
+    /// By the sticker value, compared as strings.
+    Value,
+    /// By the sticker value, compared as integers.
+    ValueInt,
+}
+
 /// Modes to target a song with a command.
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum Song {
@@ -106,4 +270,14 @@ pub trait Command {
 
     /// Create the "raw" command representation for transmission.
     fn into_command(self) -> RawCommand;
+
+    /// The minimum protocol version (as `(major, minor, patch)`) the server must support for the
+    /// command as configured, or `None` if it is supported by all versions.
+    ///
+    /// The client checks this before sending the command, and fails with
+    /// [`CommandError::UnsupportedByServer`](crate::CommandError::UnsupportedByServer) if the
+    /// server is too old.
+    fn min_protocol_version(&self) -> Option<(u32, u32, u32)> {
+        None
+    }
 }
@@ -0,0 +1,263 @@
+use std::collections::{HashMap, VecDeque};
+
+use crate::commands::{responses as res, Add, Command, Delete, Move, SongId, SongPosition};
+use crate::raw::RawCommand;
+
+/// A single edit of the queue, as computed by [`QueueEdit::diff`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum QueueEdit {
+    /// Remove a song from the queue.
+    Delete(Delete),
+    /// Move a song within the queue.
+    Move(Move),
+    /// Add a new song to the queue.
+    Add(Add),
+}
+
+/// Entry of the simulated queue while computing the edits.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Entry {
+    Existing(SongId),
+    Added(usize),
+}
+
+impl QueueEdit {
+    /// Compute the edits which transform the `current` queue into a queue containing the songs
+    /// with the `desired` URIs, in order.
+    ///
+    /// Songs already in the queue are kept (and moved if necessary) instead of being added again,
+    /// and the number of moves is kept minimal. The edits have to be applied in order, which can
+    /// be done in a single request by sending them as a [command list](crate::Client::command_list).
+    ///
+    /// ```no_run
+    /// use mpd_client::{commands::{Queue, QueueEdit}, Client};
+    ///
+    /// # async fn example(client: Client) -> Result<(), mpd_client::CommandError> {
+    /// let desired = vec![String::from("foo.flac"), String::from("bar.flac")];
+    ///
+    /// let queue = client.command(Queue).await?;
+    /// client.command_list(QueueEdit::diff(&queue, &desired)).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn diff(current: &[res::SongInQueue], desired: &[String]) -> Vec<QueueEdit> {
+        // Assign songs already in the queue to the desired URIs, in order of appearance
+        let mut available: HashMap<&str, VecDeque<(usize, SongId)>> = HashMap::new();
+        for (index, song) in current.iter().enumerate() {
+            available
+                .entry(song.song.url.as_str())
+                .or_default()
+                .push_back((index, song.id));
+        }
+
+        let assigned: Vec<Option<(usize, SongId)>> = desired
+            .iter()
+            .map(|uri| available.get_mut(uri.as_str())?.pop_front())
+            .collect();
+
+        let mut edits = Vec::new();
+
+        // Songs which were not assigned are removed
+        let mut kept = vec![false; current.len()];
+        for (index, _) in assigned.iter().flatten() {
+            kept[*index] = true;
+        }
+
+        let mut queue = Vec::with_capacity(desired.len());
+        for (song, kept) in current.iter().zip(kept) {
+            if kept {
+                queue.push(Entry::Existing(song.id));
+            } else {
+                edits.push(QueueEdit::Delete(Delete::id(song.id)));
+            }
+        }
+
+        // The longest sequence of kept songs which are already in the desired order doesn't need
+        // to be moved. Every other song is placed right after its predecessor in the desired order.
+        let indices: Vec<usize> = assigned.iter().flatten().map(|(index, _)| *index).collect();
+        let anchored = longest_increasing_subsequence(&indices);
+
+        let mut previous = None;
+        for (position, assigned) in assigned.iter().enumerate() {
+            let entry = match assigned {
+                Some((index, id)) => {
+                    let entry = Entry::Existing(*id);
+
+                    if anchored.binary_search(index).is_ok() {
+                        previous = Some(entry);
+                        continue;
+                    }
+
+                    let current = queue.iter().position(|e| *e == entry).unwrap();
+                    queue.remove(current);
+                    entry
+                }
+                None => Entry::Added(position),
+            };
+
+            let target = match previous {
+                Some(previous) => queue.iter().position(|e| *e == previous).unwrap() + 1,
+                None => 0,
+            };
+            queue.insert(target, entry);
+            previous = Some(entry);
+
+            let target = SongPosition(target);
+            edits.push(match entry {
+                Entry::Existing(id) => QueueEdit::Move(Move::id(id).to_position(target)),
+                Entry::Added(position) => {
+                    QueueEdit::Add(Add::uri(desired[position].clone()).at(target))
+                }
+            });
+        }
+
+        edits
+    }
+}
+
+impl Command for QueueEdit {
+    type Response = res::Empty;
+
+    fn into_command(self) -> RawCommand {
+        match self {
+            QueueEdit::Delete(command) => command.into_command(),
+            QueueEdit::Move(command) => command.into_command(),
+            QueueEdit::Add(command) => command.into_command(),
+        }
+    }
+
+    fn min_protocol_version(&self) -> Option<(u32, u32, u32)> {
+        match self {
+            QueueEdit::Delete(command) => command.min_protocol_version(),
+            QueueEdit::Move(command) => command.min_protocol_version(),
+            QueueEdit::Add(command) => command.min_protocol_version(),
+        }
+    }
+}
+
+/// Returns the values of the longest strictly increasing subsequence of `values`, sorted.
+fn longest_increasing_subsequence(values: &[usize]) -> Vec<usize> {
+    // Index into `values` of the smallest tail of an increasing subsequence of each length
+    let mut tails: Vec<usize> = Vec::new();
+    let mut predecessors = vec![None; values.len()];
+
+    for (i, value) in values.iter().enumerate() {
+        let length = tails.partition_point(|&t| values[t] < *value);
+
+        if length > 0 {
+            predecessors[i] = Some(tails[length - 1]);
+        }
+
+        if length == tails.len() {
+            tails.push(i);
+        } else {
+            tails[length] = i;
+        }
+    }
+
+    let mut out = Vec::with_capacity(tails.len());
+    let mut next = tails.last().copied();
+
+    while let Some(i) = next {
+        out.push(values[i]);
+        next = predecessors[i];
+    }
+
+    out.reverse();
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::responses::{Song, SongInQueue};
+
+    fn queue(uris: &[&str]) -> Vec<SongInQueue> {
+        uris.iter()
+            .enumerate()
+            .map(|(i, uri)| SongInQueue {
+                position: SongPosition(i),
+                id: SongId(i as u64 + 1),
+                range: None,
+                priority: 0,
+                song: Song {
+                    url: String::from(*uri),
+                    duration: None,
+                    tags: Default::default(),
+                    format: None,
+                    last_modified: None,
+                },
+            })
+            .collect()
+    }
+
+    /// Apply the edits to a list of URIs, following the semantics of the server.
+    fn apply(current: &[SongInQueue], desired: &[String], edits: Vec<QueueEdit>) -> Vec<String> {
+        let mut queue: Vec<(Option<SongId>, String)> = current
+            .iter()
+            .map(|s| (Some(s.id), s.song.url.clone()))
+            .collect();
+
+        for edit in edits {
+            let ids = current.iter().map(|s| s.id);
+            let positions = 0..=queue.len();
+
+            if let Some(id) = ids
+                .clone()
+                .find(|id| edit == QueueEdit::Delete(Delete::id(*id)))
+            {
+                queue.retain(|(i, _)| *i != Some(id));
+            } else if let Some((id, to)) = ids
+                .flat_map(|id| positions.clone().map(move |to| (id, to)))
+                .find(|(id, to)| {
+                    edit == QueueEdit::Move(Move::id(*id).to_position(SongPosition(*to)))
+                })
+            {
+                let from = queue.iter().position(|(i, _)| *i == Some(id)).unwrap();
+                let entry = queue.remove(from);
+                queue.insert(to, entry);
+            } else if let Some((uri, at)) = desired
+                .iter()
+                .flat_map(|uri| positions.clone().map(move |at| (uri, at)))
+                .find(|(uri, at)| {
+                    edit == QueueEdit::Add(Add::uri(uri.to_string()).at(SongPosition(*at)))
+                })
+            {
+                queue.insert(at, (None, uri.clone()));
+            } else {
+                panic!("unexpected edit {:?}", edit);
+            }
+        }
+
+        queue.into_iter().map(|(_, uri)| uri).collect()
+    }
+
+    fn check(current: &[&str], desired: &[&str], expected_edits: usize) {
+        let current = queue(current);
+        let desired: Vec<String> = desired.iter().map(|s| String::from(*s)).collect();
+
+        let edits = QueueEdit::diff(&current, &desired);
+        assert_eq!(edits.len(), expected_edits, "{:?}", edits);
+        assert_eq!(apply(&current, &desired, edits), desired);
+    }
+
+    #[test]
+    fn queue_diff() {
+        check(&[], &[], 0);
+        check(&["a", "b"], &["a", "b"], 0);
+        check(&[], &["a", "b"], 2);
+        check(&["a", "b"], &[], 2);
+        check(&["a", "b", "c", "d"], &["b", "c", "d", "a"], 1);
+        check(&["a", "b", "c", "d"], &["d", "a", "b", "c"], 1);
+        check(&["a", "b", "c"], &["c", "b", "a"], 2);
+        check(&["a", "x", "b"], &["a", "b", "y"], 2);
+        check(&["a", "a", "b"], &["b", "a", "c", "a"], 2);
+    }
+
+    #[test]
+    fn increasing_subsequence() {
+        assert_eq!(longest_increasing_subsequence(&[]), Vec::<usize>::new());
+        assert_eq!(longest_increasing_subsequence(&[3, 0, 1, 2]), vec![0, 1, 2]);
+        assert_eq!(longest_increasing_subsequence(&[2, 1, 0]).len(), 1);
+    }
+}
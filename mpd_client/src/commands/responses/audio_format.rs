@@ -0,0 +1,182 @@
+use mpd_protocol::command::Argument;
+
+use std::borrow::Cow;
+use std::error::Error;
+use std::fmt;
+use std::str::FromStr;
+
+/// Base rate of DSD formats, `dsd64` is 64 times this rate.
+const DSD_BASE_RATE: u32 = 44100;
+
+/// MPD specifies the rate of DSD formats in bytes rather than bits, e.g. `352800:dsd:2` is
+/// `dsd64:2`.
+const DSD_BITS_PER_BYTE: u32 = 8;
+
+/// Format of audio data, such as the format of the [currently playing audio][super::Status] or
+/// of a [song][super::Song].
+///
+/// This is parsed from and displayed as the syntax used by MPD, e.g. `44100:16:2`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct AudioFormat {
+    /// Sample rate in Hz. For DSD, this is the bit rate of a single channel, e.g. 2822400 for
+    /// `dsd64`.
+    pub sample_rate: u32,
+    /// Format of individual samples.
+    pub bits: SampleFormat,
+    /// Number of channels.
+    pub channels: u8,
+}
+
+/// Format of individual samples in an [`AudioFormat`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum SampleFormat {
+    /// Integer samples with the given number of bits.
+    Bits(u8),
+    /// 32 bit floating point samples (`f`).
+    Float,
+    /// Direct Stream Digital (`dsd`).
+    Dsd,
+}
+
+impl FromStr for AudioFormat {
+    type Err = AudioFormatError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        // `dsd64:2` is a shorthand for DSD at 64 times the base rate
+        if let Some(dsd) = s.strip_prefix("dsd") {
+            let (multiplier, channels) = dsd.split_once(':').ok_or(AudioFormatError)?;
+            let multiplier: u32 = multiplier.parse().map_err(|_| AudioFormatError)?;
+
+            return Ok(AudioFormat {
+                sample_rate: multiplier
+                    .checked_mul(DSD_BASE_RATE)
+                    .ok_or(AudioFormatError)?,
+                bits: SampleFormat::Dsd,
+                channels: channels.parse().map_err(|_| AudioFormatError)?,
+            });
+        }
+
+        let mut parts = s.splitn(3, ':');
+        let (sample_rate, bits, channels) = match (parts.next(), parts.next(), parts.next()) {
+            (Some(sample_rate), Some(bits), Some(channels)) => (sample_rate, bits, channels),
+            _ => return Err(AudioFormatError),
+        };
+
+        let mut sample_rate: u32 = sample_rate.parse().map_err(|_| AudioFormatError)?;
+        let bits = match bits {
+            "f" => SampleFormat::Float,
+            "dsd" => {
+                sample_rate = sample_rate
+                    .checked_mul(DSD_BITS_PER_BYTE)
+                    .ok_or(AudioFormatError)?;
+                SampleFormat::Dsd
+            }
+            bits => SampleFormat::Bits(bits.parse().map_err(|_| AudioFormatError)?),
+        };
+
+        Ok(AudioFormat {
+            sample_rate,
+            bits,
+            channels: channels.parse().map_err(|_| AudioFormatError)?,
+        })
+    }
+}
+
+impl fmt::Display for AudioFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.bits {
+            SampleFormat::Dsd if self.sample_rate.is_multiple_of(DSD_BASE_RATE) => write!(
+                f,
+                "dsd{}:{}",
+                self.sample_rate / DSD_BASE_RATE,
+                self.channels
+            ),
+            SampleFormat::Dsd => write!(
+                f,
+                "{}:dsd:{}",
+                self.sample_rate / DSD_BITS_PER_BYTE,
+                self.channels
+            ),
+            SampleFormat::Float => write!(f, "{}:f:{}", self.sample_rate, self.channels),
+            SampleFormat::Bits(bits) => {
+                write!(f, "{}:{}:{}", self.sample_rate, bits, self.channels)
+            }
+        }
+    }
+}
+
+impl Argument for AudioFormat {
+    fn render(self) -> Cow<'static, str> {
+        Cow::Owned(self.to_string())
+    }
+}
+
+/// Error returned when parsing an invalid [`AudioFormat`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct AudioFormatError;
+
+impl fmt::Display for AudioFormatError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid audio format")
+    }
+}
+
+impl Error for AudioFormatError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn format(sample_rate: u32, bits: SampleFormat, channels: u8) -> AudioFormat {
+        AudioFormat {
+            sample_rate,
+            bits,
+            channels,
+        }
+    }
+
+    #[test]
+    fn parse() {
+        assert_eq!(
+            "44100:16:2".parse(),
+            Ok(format(44100, SampleFormat::Bits(16), 2))
+        );
+        assert_eq!(
+            "192000:f:6".parse(),
+            Ok(format(192000, SampleFormat::Float, 6))
+        );
+        assert_eq!("dsd64:2".parse(), Ok(format(2822400, SampleFormat::Dsd, 2)));
+        assert_eq!(
+            "352800:dsd:2".parse::<AudioFormat>(),
+            "dsd64:2".parse::<AudioFormat>()
+        );
+        assert_eq!(
+            "384000:dsd:2".parse(),
+            Ok(format(3072000, SampleFormat::Dsd, 2))
+        );
+
+        assert_eq!("44100:16".parse::<AudioFormat>(), Err(AudioFormatError));
+        assert_eq!("44100:x:2".parse::<AudioFormat>(), Err(AudioFormatError));
+        assert_eq!("dsd:2".parse::<AudioFormat>(), Err(AudioFormatError));
+    }
+
+    #[test]
+    fn display() {
+        for raw in [
+            "44100:16:2",
+            "48000:24:1",
+            "192000:f:6",
+            "dsd128:2",
+            "384000:dsd:2",
+        ] {
+            assert_eq!(raw.parse::<AudioFormat>().unwrap().to_string(), raw);
+        }
+
+        assert_eq!(
+            "352800:dsd:2".parse::<AudioFormat>().unwrap().to_string(),
+            "dsd64:2"
+        );
+    }
+}
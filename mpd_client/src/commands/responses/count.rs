@@ -0,0 +1,113 @@
+use std::time::Duration;
+
+use super::{parse_duration, ErrorKind, KeyValuePair, TypedResponseError};
+
+/// Response to the [`count`] command.
+///
+/// [`count`]: crate::commands::definitions::Count
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[non_exhaustive]
+pub struct Count {
+    /// The value of the group tag, if the results were grouped.
+    pub group: Option<String>,
+    /// Number of songs.
+    pub songs: u64,
+    /// Total playtime of the songs.
+    pub playtime: Duration,
+}
+
+impl Count {
+    pub(super) fn parse_frame(
+        frame: impl IntoIterator<Item = KeyValuePair>,
+    ) -> Result<Vec<Self>, TypedResponseError> {
+        let mut out = Vec::new();
+
+        let mut group = None;
+        let mut songs = None;
+
+        for (key, value) in frame {
+            match &*key {
+                "songs" => {
+                    let count = value.parse().map_err(|e| TypedResponseError {
+                        field: "songs",
+                        kind: ErrorKind::MalformedInteger(e),
+                    })?;
+
+                    songs = Some(count);
+                }
+                "playtime" => {
+                    let playtime = parse_duration("playtime", &value)?;
+                    let songs = songs.take().ok_or(TypedResponseError {
+                        field: "songs",
+                        kind: ErrorKind::Missing,
+                    })?;
+
+                    out.push(Count {
+                        group: group.take(),
+                        songs,
+                        playtime,
+                    });
+                }
+                // Any other field is the value of the group tag, starting a new group
+                _ => group = Some(value),
+            }
+        }
+
+        Ok(out)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::responses::tests::key_value_pairs;
+
+    #[test]
+    fn ungrouped() {
+        let input = key_value_pairs(vec![("songs", "12"), ("playtime", "3600")]);
+
+        assert_eq!(
+            Count::parse_frame(input),
+            Ok(vec![Count {
+                group: None,
+                songs: 12,
+                playtime: Duration::from_secs(3600),
+            }])
+        );
+    }
+
+    #[test]
+    fn grouped() {
+        let input = key_value_pairs(vec![
+            ("Artist", "Foo"),
+            ("songs", "2"),
+            ("playtime", "500"),
+            ("Artist", ""),
+            ("songs", "1"),
+            ("playtime", "100"),
+        ]);
+
+        assert_eq!(
+            Count::parse_frame(input),
+            Ok(vec![
+                Count {
+                    group: Some(String::from("Foo")),
+                    songs: 2,
+                    playtime: Duration::from_secs(500),
+                },
+                Count {
+                    group: Some(String::new()),
+                    songs: 1,
+                    playtime: Duration::from_secs(100),
+                },
+            ])
+        );
+
+        let input = key_value_pairs(vec![("Artist", "Foo"), ("playtime", "500")]);
+        assert_eq!(
+            Count::parse_frame(input).unwrap_err().kind,
+            ErrorKind::Missing
+        );
+    }
+}
@@ -0,0 +1,81 @@
+use super::{ErrorKind, KeyValuePair, TypedResponseError};
+
+/// A decoder plugin, as returned by the [`decoders`] command.
+///
+/// [`decoders`]: crate::commands::definitions::Decoders
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[non_exhaustive]
+pub struct Decoder {
+    /// Name of the plugin.
+    pub plugin: String,
+    /// File suffixes supported by the plugin.
+    pub suffixes: Vec<String>,
+    /// MIME types supported by the plugin.
+    pub mime_types: Vec<String>,
+}
+
+impl Decoder {
+    pub(super) fn parse_frame(
+        frame: impl IntoIterator<Item = KeyValuePair>,
+    ) -> Result<Vec<Self>, TypedResponseError> {
+        let mut out: Vec<Decoder> = Vec::new();
+
+        for (key, value) in frame {
+            match (&*key, out.last_mut()) {
+                ("plugin", _) => out.push(Decoder {
+                    plugin: value,
+                    suffixes: Vec::new(),
+                    mime_types: Vec::new(),
+                }),
+                ("suffix", Some(decoder)) => decoder.suffixes.push(value),
+                ("mime_type", Some(decoder)) => decoder.mime_types.push(value),
+                _ => {
+                    return Err(TypedResponseError {
+                        field: "plugin",
+                        kind: ErrorKind::UnexpectedField(key.as_ref().to_owned()),
+                    })
+                }
+            }
+        }
+
+        Ok(out)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::responses::tests::key_value_pairs;
+
+    #[test]
+    fn decoders() {
+        let input = key_value_pairs(vec![
+            ("plugin", "mad"),
+            ("suffix", "mp3"),
+            ("suffix", "mp2"),
+            ("mime_type", "audio/mpeg"),
+            ("plugin", "pcm"),
+            ("mime_type", "audio/L16"),
+        ]);
+
+        assert_eq!(
+            Decoder::parse_frame(input),
+            Ok(vec![
+                Decoder {
+                    plugin: String::from("mad"),
+                    suffixes: vec![String::from("mp3"), String::from("mp2")],
+                    mime_types: vec![String::from("audio/mpeg")],
+                },
+                Decoder {
+                    plugin: String::from("pcm"),
+                    suffixes: Vec::new(),
+                    mime_types: vec![String::from("audio/L16")],
+                },
+            ])
+        );
+
+        let input = key_value_pairs(vec![("suffix", "mp3")]);
+        assert!(Decoder::parse_frame(input).is_err());
+    }
+}
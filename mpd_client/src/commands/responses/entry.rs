@@ -0,0 +1,194 @@
+use chrono::{DateTime, FixedOffset};
+
+use super::{ErrorKind, KeyValuePair, Song, TypedResponseError};
+
+/// An entry in a directory listing, as returned by the [`lsinfo`] and [`listfiles`] commands.
+///
+/// [`lsinfo`]: crate::commands::definitions::ListInfo
+/// [`listfiles`]: crate::commands::definitions::ListFiles
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[non_exhaustive]
+pub enum DirectoryEntry {
+    /// A subdirectory.
+    Directory {
+        /// Path of the directory.
+        path: String,
+        /// Last modification date of the directory.
+        last_modified: Option<DateTime<FixedOffset>>,
+    },
+    /// A song in the database (only returned by `lsinfo`).
+    Song(Song),
+    /// A stored playlist (only returned by `lsinfo`).
+    Playlist {
+        /// Path of the playlist.
+        path: String,
+        /// Last modification date of the playlist.
+        last_modified: Option<DateTime<FixedOffset>>,
+    },
+    /// A raw file, which is not necessarily a song (only returned by `listfiles`).
+    File {
+        /// Path of the file.
+        path: String,
+        /// Size of the file in bytes.
+        size: Option<u64>,
+        /// Last modification date of the file.
+        last_modified: Option<DateTime<FixedOffset>>,
+    },
+}
+
+impl DirectoryEntry {
+    pub(super) fn parse_frame(
+        frame: impl IntoIterator<Item = KeyValuePair>,
+    ) -> Result<Vec<Self>, TypedResponseError> {
+        let mut entries: Vec<Vec<KeyValuePair>> = Vec::new();
+
+        for (key, value) in frame {
+            match (&*key, entries.last_mut()) {
+                ("file" | "directory" | "playlist", _) => entries.push(vec![(key, value)]),
+                (_, Some(entry)) => entry.push((key, value)),
+                (_, None) => {
+                    return Err(TypedResponseError {
+                        field: "file",
+                        kind: ErrorKind::UnexpectedField(key.as_ref().to_owned()),
+                    })
+                }
+            }
+        }
+
+        entries
+            .into_iter()
+            .map(DirectoryEntry::parse_entry)
+            .collect()
+    }
+
+    fn parse_entry(fields: Vec<KeyValuePair>) -> Result<Self, TypedResponseError> {
+        // `listfiles` always sends the size of a file immediately after its name, which never
+        // happens for songs returned by `lsinfo`
+        let is_file = matches!(fields.get(1), Some((key, _)) if &**key == "size");
+
+        if &*fields[0].0 == "file" && !is_file {
+            let mut songs = Song::parse_frame(fields, Some(1))?;
+            return Ok(DirectoryEntry::Song(songs.pop().unwrap()));
+        }
+
+        let mut fields = fields.into_iter();
+        let (kind, path) = fields.next().unwrap();
+
+        let mut size = None;
+        let mut last_modified = None;
+
+        for (key, value) in fields {
+            match &*key {
+                "size" => {
+                    let parsed = value.parse().map_err(|e| TypedResponseError {
+                        field: "size",
+                        kind: ErrorKind::MalformedInteger(e),
+                    })?;
+
+                    size = Some(parsed);
+                }
+                "Last-Modified" => {
+                    let ts =
+                        DateTime::parse_from_rfc3339(&value).map_err(|e| TypedResponseError {
+                            field: "Last-Modified",
+                            kind: ErrorKind::MalformedTimestamp(e),
+                        })?;
+
+                    last_modified = Some(ts);
+                }
+                _ => (),
+            }
+        }
+
+        Ok(match &*kind {
+            "directory" => DirectoryEntry::Directory {
+                path,
+                last_modified,
+            },
+            "playlist" => DirectoryEntry::Playlist {
+                path,
+                last_modified,
+            },
+            _ => DirectoryEntry::File {
+                path,
+                size,
+                last_modified,
+            },
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::responses::tests::key_value_pairs;
+
+    #[test]
+    fn lsinfo() {
+        let ts = "2020-06-12T17:53:00Z";
+        let input = key_value_pairs(vec![
+            ("directory", "foo"),
+            ("Last-Modified", ts),
+            ("file", "bar.flac"),
+            ("Last-Modified", ts),
+            ("Title", "Bar"),
+            ("playlist", "baz.m3u"),
+        ]);
+
+        let entries = DirectoryEntry::parse_frame(input).unwrap();
+
+        assert_eq!(entries.len(), 3);
+        assert_eq!(
+            entries[0],
+            DirectoryEntry::Directory {
+                path: String::from("foo"),
+                last_modified: Some(DateTime::parse_from_rfc3339(ts).unwrap()),
+            }
+        );
+        match &entries[1] {
+            DirectoryEntry::Song(song) => {
+                assert_eq!(song.url, "bar.flac");
+                assert_eq!(song.title(), Some("Bar"));
+            }
+            other => panic!("expected song, got {:?}", other),
+        }
+        assert_eq!(
+            entries[2],
+            DirectoryEntry::Playlist {
+                path: String::from("baz.m3u"),
+                last_modified: None,
+            }
+        );
+    }
+
+    #[test]
+    fn listfiles() {
+        let input = key_value_pairs(vec![
+            ("directory", "foo"),
+            ("file", "cover.jpg"),
+            ("size", "1234"),
+            ("Last-Modified", "2020-06-12T17:53:00Z"),
+        ]);
+
+        assert_eq!(
+            DirectoryEntry::parse_frame(input),
+            Ok(vec![
+                DirectoryEntry::Directory {
+                    path: String::from("foo"),
+                    last_modified: None,
+                },
+                DirectoryEntry::File {
+                    path: String::from("cover.jpg"),
+                    size: Some(1234),
+                    last_modified: Some(
+                        DateTime::parse_from_rfc3339("2020-06-12T17:53:00Z").unwrap()
+                    ),
+                },
+            ])
+        );
+
+        let input = key_value_pairs(vec![("size", "1234")]);
+        assert!(DirectoryEntry::parse_frame(input).is_err());
+    }
+}
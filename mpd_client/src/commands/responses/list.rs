@@ -5,12 +5,26 @@ use crate::tag::Tag;
 ///
 /// [`list`]: crate::commands::definitions::List
 #[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[non_exhaustive]
 pub struct List {
     /// The fields returned by the command, in the order returned by MPD.
     pub fields: Vec<(Tag, String)>,
 }
 
+/// A single value in a [grouped][List::grouped] `list` response.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[non_exhaustive]
+pub struct ListEntry {
+    /// The tag of this value.
+    pub tag: Tag,
+    /// The value.
+    pub value: String,
+    /// The values nested in this group. Empty for values of the listed tag itself.
+    pub children: Vec<ListEntry>,
+}
+
 impl List {
     pub(crate) fn from_frame(fields: impl IntoIterator<Item = KeyValuePair>) -> Self {
         let fields = fields
@@ -20,4 +34,128 @@ impl List {
 
         Self { fields }
     }
+
+    /// Get the response as a tree of values, nested according to the `group` clauses of the
+    /// command.
+    ///
+    /// The top level contains the values of the outermost group. If the command had no `group`
+    /// clauses, this only contains values of the listed tag.
+    pub fn grouped(&self) -> Vec<ListEntry> {
+        // MPD prints the tags in the order of nesting, so the position at which a tag is first
+        // encountered is its depth
+        let mut depths: Vec<&Tag> = Vec::new();
+        let mut roots = Vec::new();
+        let mut open: Vec<ListEntry> = Vec::new();
+
+        for (tag, value) in &self.fields {
+            let depth = match depths.iter().position(|t| *t == tag) {
+                Some(depth) => depth,
+                None => {
+                    depths.push(tag);
+                    depths.len() - 1
+                }
+            };
+
+            while open.len() > depth {
+                close_entry(&mut open, &mut roots);
+            }
+
+            open.push(ListEntry {
+                tag: tag.clone(),
+                value: value.clone(),
+                children: Vec::new(),
+            });
+        }
+
+        while !open.is_empty() {
+            close_entry(&mut open, &mut roots);
+        }
+
+        roots
+    }
+}
+
+fn close_entry(open: &mut Vec<ListEntry>, roots: &mut Vec<ListEntry>) {
+    let entry = open.pop().unwrap();
+
+    match open.last_mut() {
+        Some(parent) => parent.children.push(entry),
+        None => roots.push(entry),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(tag: Tag, value: &str, children: Vec<ListEntry>) -> ListEntry {
+        ListEntry {
+            tag,
+            value: String::from(value),
+            children,
+        }
+    }
+
+    fn list(fields: Vec<(Tag, &str)>) -> List {
+        List {
+            fields: fields
+                .into_iter()
+                .map(|(tag, value)| (tag, String::from(value)))
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn ungrouped() {
+        let list = list(vec![(Tag::Album, "A"), (Tag::Album, "B")]);
+
+        assert_eq!(
+            list.grouped(),
+            vec![
+                entry(Tag::Album, "A", vec![]),
+                entry(Tag::Album, "B", vec![]),
+            ]
+        );
+    }
+
+    #[test]
+    fn nested_groups() {
+        let list = list(vec![
+            (Tag::AlbumArtist, "Foo"),
+            (Tag::Date, "2001"),
+            (Tag::Album, "A"),
+            (Tag::Album, "B"),
+            (Tag::Date, "2005"),
+            (Tag::Album, "C"),
+            (Tag::AlbumArtist, "Bar"),
+            (Tag::Date, ""),
+            (Tag::Album, "D"),
+        ]);
+
+        assert_eq!(
+            list.grouped(),
+            vec![
+                entry(
+                    Tag::AlbumArtist,
+                    "Foo",
+                    vec![
+                        entry(
+                            Tag::Date,
+                            "2001",
+                            vec![
+                                entry(Tag::Album, "A", vec![]),
+                                entry(Tag::Album, "B", vec![]),
+                            ]
+                        ),
+                        entry(Tag::Date, "2005", vec![entry(Tag::Album, "C", vec![])]),
+                    ]
+                ),
+                entry(
+                    Tag::AlbumArtist,
+                    "Bar",
+                    vec![entry(Tag::Date, "", vec![entry(Tag::Album, "D", vec![])])]
+                ),
+            ]
+        );
+    }
 }
@@ -0,0 +1,82 @@
+use super::{ErrorKind, KeyValuePair, TypedResponseError};
+
+/// A message received on a client-to-client channel, as returned by the [`readmessages`]
+/// command.
+///
+/// [`readmessages`]: crate::commands::definitions::ReadMessages
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[non_exhaustive]
+pub struct ChannelMessage {
+    /// The channel the message was sent on.
+    pub channel: String,
+    /// The message.
+    pub message: String,
+}
+
+impl ChannelMessage {
+    pub(super) fn parse_frame(
+        frame: impl IntoIterator<Item = KeyValuePair>,
+    ) -> Result<Vec<Self>, TypedResponseError> {
+        let mut out = Vec::new();
+        let mut channel = None;
+
+        for (key, value) in frame {
+            match &*key {
+                "channel" => channel = Some(value),
+                "message" => {
+                    let channel = channel.take().ok_or(TypedResponseError {
+                        field: "channel",
+                        kind: ErrorKind::Missing,
+                    })?;
+
+                    out.push(ChannelMessage {
+                        channel,
+                        message: value,
+                    });
+                }
+                _ => {
+                    return Err(TypedResponseError {
+                        field: "channel",
+                        kind: ErrorKind::UnexpectedField(key.as_ref().to_owned()),
+                    })
+                }
+            }
+        }
+
+        Ok(out)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::responses::tests::key_value_pairs;
+
+    #[test]
+    fn messages() {
+        let input = key_value_pairs(vec![
+            ("channel", "foo"),
+            ("message", "hello"),
+            ("channel", "bar"),
+            ("message", "world"),
+        ]);
+
+        assert_eq!(
+            ChannelMessage::parse_frame(input),
+            Ok(vec![
+                ChannelMessage {
+                    channel: String::from("foo"),
+                    message: String::from("hello"),
+                },
+                ChannelMessage {
+                    channel: String::from("bar"),
+                    message: String::from("world"),
+                },
+            ])
+        );
+
+        let input = key_value_pairs(vec![("message", "hello")]);
+        assert!(ChannelMessage::parse_frame(input).is_err());
+    }
+}
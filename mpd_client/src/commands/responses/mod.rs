@@ -3,12 +3,21 @@
 #[macro_use]
 mod util_macros;
 
+mod audio_format;
+mod count;
+mod decoder;
+mod entry;
 mod list;
+mod message;
+mod mount;
+mod output;
 mod playlist;
 mod song;
+mod sticker;
 
 use bytes::Bytes;
-use chrono::ParseError;
+use chrono::{DateTime, ParseError, Utc};
+use tracing::warn;
 
 use std::error::Error;
 use std::fmt;
@@ -16,14 +25,22 @@ use std::num::{ParseFloatError, ParseIntError};
 use std::sync::Arc;
 use std::time::Duration;
 
-use crate::commands::{SingleMode, SongId, SongPosition};
+use crate::commands::{ReplayGainMode, SingleMode, SongId, SongPosition, StickerType, UpdateJob};
 use crate::raw::Frame;
 use crate::sealed;
 use crate::tag::Tag;
 
-pub use list::List;
+pub use audio_format::{AudioFormat, AudioFormatError, SampleFormat};
+pub use count::Count;
+pub use decoder::Decoder;
+pub use entry::DirectoryEntry;
+pub use list::{List, ListEntry};
+pub use message::ChannelMessage;
+pub use mount::Mount;
+pub use output::Output;
 pub use playlist::Playlist;
 pub use song::{Song, SongInQueue, SongRange};
+pub use sticker::{Sticker, StickerMatch, StickerNameTypes};
 
 type KeyValuePair = (Arc<str>, String);
 
@@ -64,6 +81,15 @@ enum ErrorKind {
     MalformedTimestamp(ParseError),
 }
 
+impl TypedResponseError {
+    pub(crate) fn invalid_value(field: &'static str, value: String) -> Self {
+        Self {
+            field,
+            kind: ErrorKind::InvalidValue(value),
+        }
+    }
+}
+
 impl fmt::Display for TypedResponseError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match &self.kind {
@@ -126,6 +152,7 @@ impl Response for Empty {
 
 /// Possible playback states.
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[allow(missing_docs)]
 pub enum PlayState {
     Stopped,
@@ -140,6 +167,7 @@ pub enum PlayState {
 /// [`status`]: crate::commands::definitions::Status
 /// [status-command]: https://www.musicpd.org/doc/html/protocol.html#command-status
 #[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[allow(missing_docs)]
 #[non_exhaustive]
 pub struct Status {
@@ -147,7 +175,8 @@ pub struct Status {
     pub state: PlayState,
     pub repeat: bool,
     pub random: bool,
-    pub consume: bool,
+    /// **NOTE**: [`SingleMode::Oneshot`] is only supported on protocol versions 0.24 and later.
+    pub consume: SingleMode,
     pub single: SingleMode,
     pub playlist_version: u32,
     pub playlist_length: usize,
@@ -156,8 +185,12 @@ pub struct Status {
     pub elapsed: Option<Duration>,
     pub duration: Option<Duration>,
     pub bitrate: Option<u64>,
+    /// Format of the audio being played.
+    pub audio_format: Option<AudioFormat>,
     pub crossfade: Duration,
-    pub update_job: Option<u64>,
+    /// Amount of time to overlap songs by using MixRamp, or `None` if MixRamp is disabled.
+    pub mixramp_delay: Option<Duration>,
+    pub update_job: Option<UpdateJob>,
     pub error: Option<String>,
     /// Name of the non-default partition this client is active on. Will be `None` if the default
     /// partition is active or if the server doesn't send the field at all.
@@ -167,19 +200,23 @@ pub struct Status {
 impl sealed::Sealed for Status {}
 impl Response for Status {
     fn from_frame(mut raw: Frame) -> Result<Self, TypedResponseError> {
-        let single = match raw.get("single") {
-            None => SingleMode::Disabled,
-            Some(val) => match val.as_str() {
-                "0" => SingleMode::Disabled,
-                "1" => SingleMode::Enabled,
-                "oneshot" => SingleMode::Oneshot,
-                _ => {
-                    return Err(TypedResponseError {
-                        field: "single",
-                        kind: ErrorKind::InvalidValue(val),
-                    })
+        // MPD may report formats this doesn't understand, which shouldn't fail the response
+        let audio_format = match raw.get("audio") {
+            Some(val) => match val.parse() {
+                Ok(format) => Some(format),
+                Err(_) => {
+                    warn!(format = ?val, "failed to parse audio format");
+                    None
                 }
             },
+            None => None,
+        };
+
+        // The server reports a delay of `nan` when MixRamp is disabled
+        let mixramp_delay = match raw.get("mixrampdelay") {
+            Some(val) if val == "nan" => None,
+            Some(val) => Some(parse!(duration, val, "mixrampdelay")),
+            None => None,
         };
 
         let duration = if let Some(val) = raw.get("duration") {
@@ -210,8 +247,8 @@ impl Response for Status {
             state: field!(raw, "state" PlayState),
             repeat: field!(raw, "repeat" boolean),
             random: field!(raw, "random" boolean),
-            consume: field!(raw, "consume" boolean),
-            single,
+            consume: field!(raw, "consume" SingleMode),
+            single: field!(raw, "single" SingleMode default SingleMode::Disabled),
             playlist_length: field!(raw, "playlistlength" integer default 0),
             playlist_version: field!(raw, "playlist" integer default 0),
             current_song: song_identifier!(raw, "song", "songid"),
@@ -219,8 +256,10 @@ impl Response for Status {
             elapsed: field!(raw, "elapsed" duration optional),
             duration,
             bitrate: field!(raw, "bitrate" integer optional),
+            audio_format,
             crossfade: field!(raw, "xfade" duration default Duration::from_secs(0)),
-            update_job: field!(raw, "update_job" integer optional),
+            mixramp_delay,
+            update_job: field!(raw, "update_job" integer optional).map(UpdateJob),
             error: raw.get("error"),
             partition,
         })
@@ -231,6 +270,7 @@ impl Response for Status {
 ///
 /// [`stats`]: crate::commands::definitions::Stats
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[allow(missing_docs)]
 #[non_exhaustive]
 pub struct Stats {
@@ -240,8 +280,8 @@ pub struct Stats {
     pub uptime: Duration,
     pub playtime: Duration,
     pub db_playtime: Duration,
-    /// Raw server UNIX timestamp of last database update.
-    pub db_last_update: u64,
+    /// Time of the last database update.
+    pub db_last_update: DateTime<Utc>,
 }
 
 impl sealed::Sealed for Stats {}
@@ -254,7 +294,33 @@ impl Response for Stats {
             uptime: field!(raw, "uptime" duration),
             playtime: field!(raw, "playtime" duration),
             db_playtime: field!(raw, "db_playtime" duration),
-            db_last_update: field!(raw, "db_update" integer),
+            db_last_update: field!(raw, "db_update" timestamp),
+        })
+    }
+}
+
+/// Response to the [`config`] command, containing the server configuration.
+///
+/// [`config`]: crate::commands::definitions::Config
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[non_exhaustive]
+pub struct Config {
+    /// Absolute path to the music directory.
+    pub music_directory: String,
+    /// Absolute path to the playlist directory, if configured.
+    pub playlist_directory: Option<String>,
+    /// Whether the server supports regular expressions in filters.
+    pub pcre: bool,
+}
+
+impl sealed::Sealed for Config {}
+impl Response for Config {
+    fn from_frame(mut raw: Frame) -> Result<Self, TypedResponseError> {
+        Ok(Self {
+            music_directory: field!(raw, "music_directory" string),
+            playlist_directory: field!(raw, "playlist_directory" string optional),
+            pcre: field!(raw, "pcre" boolean default false),
         })
     }
 }
@@ -288,6 +354,162 @@ impl Response for SongId {
     }
 }
 
+impl sealed::Sealed for Vec<(SongPosition, SongId)> {}
+impl Response for Vec<(SongPosition, SongId)> {
+    fn from_frame(frame: Frame) -> Result<Self, TypedResponseError> {
+        let mut out = Vec::new();
+        let mut position = None;
+
+        for (key, value) in frame {
+            match &*key {
+                "cpos" => position = Some(SongPosition(parse!(integer, value, "cpos"))),
+                "Id" => {
+                    let position = position.take().ok_or(TypedResponseError {
+                        field: "cpos",
+                        kind: ErrorKind::Missing,
+                    })?;
+
+                    out.push((position, SongId(parse!(integer, value, "Id"))));
+                }
+                _ => {
+                    return Err(TypedResponseError {
+                        field: "cpos",
+                        kind: ErrorKind::UnexpectedField(key.as_ref().to_owned()),
+                    })
+                }
+            }
+        }
+
+        Ok(out)
+    }
+}
+
+impl sealed::Sealed for Vec<DirectoryEntry> {}
+impl Response for Vec<DirectoryEntry> {
+    fn from_frame(frame: Frame) -> Result<Self, TypedResponseError> {
+        DirectoryEntry::parse_frame(frame)
+    }
+}
+
+impl sealed::Sealed for Vec<(String, String)> {}
+impl Response for Vec<(String, String)> {
+    fn from_frame(frame: Frame) -> Result<Self, TypedResponseError> {
+        Ok(frame
+            .into_iter()
+            .map(|(key, value)| (key.as_ref().to_owned(), value))
+            .collect())
+    }
+}
+
+impl sealed::Sealed for Sticker {}
+impl Response for Sticker {
+    fn from_frame(mut frame: Frame) -> Result<Self, TypedResponseError> {
+        Sticker::parse(field!(frame, "sticker" string))
+    }
+}
+
+impl sealed::Sealed for Vec<Sticker> {}
+impl Response for Vec<Sticker> {
+    fn from_frame(frame: Frame) -> Result<Self, TypedResponseError> {
+        Sticker::parse_frame(frame)
+    }
+}
+
+impl sealed::Sealed for Vec<StickerMatch> {}
+impl Response for Vec<StickerMatch> {
+    fn from_frame(frame: Frame) -> Result<Self, TypedResponseError> {
+        StickerMatch::parse_frame(frame)
+    }
+}
+
+impl sealed::Sealed for Vec<StickerType> {}
+impl Response for Vec<StickerType> {
+    fn from_frame(frame: Frame) -> Result<Self, TypedResponseError> {
+        frame
+            .into_iter()
+            .map(|(_, value)| sticker::parse_sticker_type(value))
+            .collect()
+    }
+}
+
+impl sealed::Sealed for Vec<StickerNameTypes> {}
+impl Response for Vec<StickerNameTypes> {
+    fn from_frame(frame: Frame) -> Result<Self, TypedResponseError> {
+        StickerNameTypes::parse_frame(frame)
+    }
+}
+
+// Responses consisting of a list of plain values, the keys of the fields are ignored
+impl sealed::Sealed for Vec<String> {}
+impl Response for Vec<String> {
+    fn from_frame(frame: Frame) -> Result<Self, TypedResponseError> {
+        Ok(frame.into_iter().map(|(_, value)| value).collect())
+    }
+}
+
+impl sealed::Sealed for Vec<Output> {}
+impl Response for Vec<Output> {
+    fn from_frame(frame: Frame) -> Result<Self, TypedResponseError> {
+        Output::parse_frame(frame)
+    }
+}
+
+impl sealed::Sealed for Vec<Mount> {}
+impl Response for Vec<Mount> {
+    fn from_frame(frame: Frame) -> Result<Self, TypedResponseError> {
+        Mount::parse_frame(frame)
+    }
+}
+
+impl sealed::Sealed for Vec<Decoder> {}
+impl Response for Vec<Decoder> {
+    fn from_frame(frame: Frame) -> Result<Self, TypedResponseError> {
+        Decoder::parse_frame(frame)
+    }
+}
+
+impl sealed::Sealed for Vec<ChannelMessage> {}
+impl Response for Vec<ChannelMessage> {
+    fn from_frame(frame: Frame) -> Result<Self, TypedResponseError> {
+        ChannelMessage::parse_frame(frame)
+    }
+}
+
+impl sealed::Sealed for UpdateJob {}
+impl Response for UpdateJob {
+    fn from_frame(mut raw: Frame) -> Result<Self, TypedResponseError> {
+        Ok(UpdateJob(field!(raw, "updating_db" integer)))
+    }
+}
+
+/// Response to the [`getvol`][crate::commands::GetVolume] command.
+///
+/// Contains the volume in percent, or `None` if the server has no mixer.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Volume(pub Option<u8>);
+
+impl sealed::Sealed for Volume {}
+impl Response for Volume {
+    fn from_frame(mut raw: Frame) -> Result<Self, TypedResponseError> {
+        Ok(Volume(field!(raw, "volume" integer optional)))
+    }
+}
+
+impl sealed::Sealed for ReplayGainMode {}
+impl Response for ReplayGainMode {
+    fn from_frame(mut raw: Frame) -> Result<Self, TypedResponseError> {
+        let mode = field!(raw, "replay_gain_mode" string);
+
+        Ok(match mode.as_str() {
+            "off" => ReplayGainMode::Off,
+            "track" => ReplayGainMode::Track,
+            "album" => ReplayGainMode::Album,
+            "auto" => ReplayGainMode::Auto,
+            _ => return Err(TypedResponseError::invalid_value("replay_gain_mode", mode)),
+        })
+    }
+}
+
 impl sealed::Sealed for Vec<Playlist> {}
 impl Response for Vec<Playlist> {
     fn from_frame(raw: Frame) -> Result<Self, TypedResponseError> {
@@ -296,6 +518,13 @@ impl Response for Vec<Playlist> {
     }
 }
 
+impl sealed::Sealed for Vec<Count> {}
+impl Response for Vec<Count> {
+    fn from_frame(frame: Frame) -> Result<Self, TypedResponseError> {
+        Count::parse_frame(frame)
+    }
+}
+
 impl sealed::Sealed for List {}
 impl Response for List {
     fn from_frame(frame: Frame) -> Result<Self, TypedResponseError> {
@@ -312,7 +541,7 @@ pub struct AlbumArt {
     /// The mime type, if known.
     pub mime: Option<String>,
     /// The raw data.
-    data: Bytes,
+    pub(crate) data: Bytes,
 }
 
 impl AlbumArt {
@@ -361,3 +590,144 @@ impl Response for Vec<Tag> {
         Ok(out)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use mpd_protocol::{Command, Connection};
+
+    use std::io::{self, Cursor, Read, Write};
+
+    use super::*;
+
+    /// Convert the given key-value pairs into the form parsed by the response types.
+    pub(super) fn key_value_pairs(
+        raw: Vec<(&'static str, &'static str)>,
+    ) -> impl Iterator<Item = KeyValuePair> {
+        raw.into_iter().map(|(k, v)| (Arc::from(k), v.to_owned()))
+    }
+
+    /// Transport sending the greeting, then the given response once a command was written.
+    struct Replay {
+        read: Cursor<Vec<u8>>,
+        response: Option<Vec<u8>>,
+    }
+
+    impl Read for Replay {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            self.read.read(buf)
+        }
+    }
+
+    impl Write for Replay {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            if let Some(response) = self.response.take() {
+                self.read = Cursor::new(response);
+            }
+
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    /// Parse the given raw response into a frame.
+    fn frame(response: &str) -> Frame {
+        let io = Replay {
+            read: Cursor::new(b"OK MPD 0.23.5\n".to_vec()),
+            response: Some(response.as_bytes().to_vec()),
+        };
+        let mut connection = Connection::connect(io).unwrap();
+
+        connection
+            .command(Command::new("test"))
+            .unwrap()
+            .single_frame()
+            .unwrap()
+    }
+
+    #[test]
+    fn volume() {
+        assert_eq!(
+            Volume::from_frame(frame("volume: 42\nOK\n")),
+            Ok(Volume(Some(42)))
+        );
+        assert_eq!(Volume::from_frame(frame("OK\n")), Ok(Volume(None)));
+    }
+
+    #[test]
+    fn status() {
+        let status = Status::from_frame(frame(
+            "volume: 50\nrepeat: 1\nrandom: 0\nsingle: 0\nconsume: oneshot\nplaylist: 7\n\
+             playlistlength: 2\nstate: play\nsong: 1\nsongid: 5\nelapsed: 12.5\nOK\n",
+        ))
+        .unwrap();
+
+        assert_eq!(status.volume, 50);
+        assert_eq!(status.state, PlayState::Playing);
+        assert!(status.repeat);
+        assert!(!status.random);
+        assert_eq!(status.single, SingleMode::Disabled);
+        assert_eq!(status.consume, SingleMode::Oneshot);
+        assert_eq!(status.playlist_version, 7);
+        assert_eq!(status.playlist_length, 2);
+        assert_eq!(status.current_song, Some((SongPosition(1), SongId(5))));
+        assert_eq!(status.elapsed, Some(Duration::from_secs_f64(12.5)));
+    }
+
+    #[test]
+    fn status_mixramp_delay() {
+        let status = "state: play\nrepeat: 0\nrandom: 0\nconsume: 0\n";
+
+        let response = format!("{status}mixrampdelay: nan\nOK\n");
+        assert_eq!(
+            Status::from_frame(frame(&response)).unwrap().mixramp_delay,
+            None
+        );
+
+        let response = format!("{status}mixrampdelay: 1.5\nOK\n");
+        assert_eq!(
+            Status::from_frame(frame(&response)).unwrap().mixramp_delay,
+            Some(Duration::from_secs_f64(1.5))
+        );
+    }
+
+    #[test]
+    fn stats() {
+        let stats = Stats::from_frame(frame(
+            "uptime: 123\nplaytime: 45\nartists: 3\nalbums: 4\nsongs: 5\ndb_playtime: 678\n\
+             db_update: 1600000000\nOK\n",
+        ))
+        .unwrap();
+
+        assert_eq!(stats.artists, 3);
+        assert_eq!(stats.albums, 4);
+        assert_eq!(stats.songs, 5);
+        assert_eq!(stats.uptime, Duration::from_secs(123));
+        assert_eq!(stats.playtime, Duration::from_secs(45));
+        assert_eq!(stats.db_playtime, Duration::from_secs(678));
+        assert_eq!(
+            stats.db_last_update,
+            DateTime::parse_from_rfc3339("2020-09-13T12:26:40Z").unwrap()
+        );
+    }
+
+    #[test]
+    fn status_audio_format() {
+        let status = "state: play\nrepeat: 0\nrandom: 0\nconsume: 0\n";
+
+        let response = format!("{status}audio: 44100:16:2\nOK\n");
+        assert_eq!(
+            Status::from_frame(frame(&response)).unwrap().audio_format,
+            Some("44100:16:2".parse().unwrap())
+        );
+
+        // An unknown format doesn't fail the whole response
+        let response = format!("{status}audio: 44100:s16le:2\nOK\n");
+        assert_eq!(
+            Status::from_frame(frame(&response)).unwrap().audio_format,
+            None
+        );
+    }
+}
@@ -0,0 +1,74 @@
+use super::{ErrorKind, KeyValuePair, TypedResponseError};
+
+/// A mounted storage, as returned by the [`listmounts`] command.
+///
+/// [`listmounts`]: crate::commands::definitions::ListMounts
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[non_exhaustive]
+pub struct Mount {
+    /// The path the storage is mounted at, relative to the root of the database. This is empty
+    /// for the music directory itself.
+    pub mount_point: String,
+    /// URI of the mounted storage, if known.
+    pub storage: Option<String>,
+}
+
+impl Mount {
+    pub(super) fn parse_frame(
+        frame: impl IntoIterator<Item = KeyValuePair>,
+    ) -> Result<Vec<Self>, TypedResponseError> {
+        let mut out: Vec<Mount> = Vec::new();
+
+        for (key, value) in frame {
+            match (&*key, out.last_mut()) {
+                ("mount", _) => out.push(Mount {
+                    mount_point: value,
+                    storage: None,
+                }),
+                ("storage", Some(mount)) => mount.storage = Some(value),
+                _ => {
+                    return Err(TypedResponseError {
+                        field: "mount",
+                        kind: ErrorKind::UnexpectedField(key.as_ref().to_owned()),
+                    })
+                }
+            }
+        }
+
+        Ok(out)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::responses::tests::key_value_pairs;
+
+    #[test]
+    fn mounts() {
+        let input = key_value_pairs(vec![
+            ("mount", ""),
+            ("storage", "/home/foo/music"),
+            ("mount", "nas"),
+            ("storage", "nfs://192.168.1.4/export/mp3"),
+        ]);
+
+        assert_eq!(
+            Mount::parse_frame(input),
+            Ok(vec![
+                Mount {
+                    mount_point: String::new(),
+                    storage: Some(String::from("/home/foo/music")),
+                },
+                Mount {
+                    mount_point: String::from("nas"),
+                    storage: Some(String::from("nfs://192.168.1.4/export/mp3")),
+                },
+            ])
+        );
+
+        let input = key_value_pairs(vec![("storage", "/home/foo/music")]);
+        assert!(Mount::parse_frame(input).is_err());
+    }
+}
@@ -0,0 +1,142 @@
+use std::collections::HashMap;
+
+use super::{ErrorKind, KeyValuePair, TypedResponseError};
+
+/// An audio output, as returned by the [`outputs`] command.
+///
+/// [`outputs`]: crate::commands::definitions::Outputs
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[non_exhaustive]
+pub struct Output {
+    /// ID of the output.
+    pub id: u32,
+    /// Name of the output, as configured.
+    pub name: String,
+    /// Name of the plugin used by the output.
+    pub plugin: Option<String>,
+    /// Whether the output is enabled.
+    pub enabled: bool,
+    /// Runtime attributes of the output, which can be changed using [`outputset`].
+    ///
+    /// [`outputset`]: crate::commands::definitions::SetOutputAttribute
+    pub attributes: HashMap<String, String>,
+}
+
+impl Output {
+    pub(super) fn parse_frame(
+        frame: impl IntoIterator<Item = KeyValuePair>,
+    ) -> Result<Vec<Self>, TypedResponseError> {
+        let mut out: Vec<Output> = Vec::new();
+
+        for (key, value) in frame {
+            if &*key == "outputid" {
+                let id = value.parse().map_err(|e| TypedResponseError {
+                    field: "outputid",
+                    kind: ErrorKind::MalformedInteger(e),
+                })?;
+
+                out.push(Output {
+                    id,
+                    name: String::new(),
+                    plugin: None,
+                    enabled: false,
+                    attributes: HashMap::new(),
+                });
+
+                continue;
+            }
+
+            let output = match out.last_mut() {
+                Some(output) => output,
+                None => {
+                    return Err(TypedResponseError {
+                        field: "outputid",
+                        kind: ErrorKind::UnexpectedField(key.as_ref().to_owned()),
+                    })
+                }
+            };
+
+            match &*key {
+                "outputname" => output.name = value,
+                "plugin" => output.plugin = Some(value),
+                "attribute" => match value.split_once('=') {
+                    Some((name, value)) => {
+                        output.attributes.insert(name.to_owned(), value.to_owned());
+                    }
+                    None => {
+                        return Err(TypedResponseError {
+                            field: "attribute",
+                            kind: ErrorKind::InvalidValue(value),
+                        })
+                    }
+                },
+                "outputenabled" => {
+                    output.enabled = match value.as_str() {
+                        "1" => true,
+                        "0" => false,
+                        _ => {
+                            return Err(TypedResponseError {
+                                field: "outputenabled",
+                                kind: ErrorKind::InvalidValue(value),
+                            })
+                        }
+                    }
+                }
+                _ => (),
+            }
+        }
+
+        Ok(out)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::responses::tests::key_value_pairs;
+
+    #[test]
+    fn outputs() {
+        let input = key_value_pairs(vec![
+            ("outputid", "0"),
+            ("outputname", "My ALSA Device"),
+            ("plugin", "alsa"),
+            ("outputenabled", "1"),
+            ("attribute", "dop=0"),
+            ("attribute", "allowed_formats="),
+            ("outputid", "1"),
+            ("outputname", "Stream"),
+            ("outputenabled", "0"),
+        ]);
+
+        assert_eq!(
+            Output::parse_frame(input),
+            Ok(vec![
+                Output {
+                    id: 0,
+                    name: String::from("My ALSA Device"),
+                    plugin: Some(String::from("alsa")),
+                    enabled: true,
+                    attributes: HashMap::from([
+                        (String::from("dop"), String::from("0")),
+                        (String::from("allowed_formats"), String::new()),
+                    ]),
+                },
+                Output {
+                    id: 1,
+                    name: String::from("Stream"),
+                    plugin: None,
+                    enabled: false,
+                    attributes: HashMap::new(),
+                },
+            ])
+        );
+
+        let input = key_value_pairs(vec![("outputname", "Stream")]);
+        assert!(Output::parse_frame(input).is_err());
+
+        let input = key_value_pairs(vec![("outputid", "0"), ("attribute", "dop")]);
+        assert!(Output::parse_frame(input).is_err());
+    }
+}
@@ -1,4 +1,5 @@
 use chrono::{DateTime, FixedOffset};
+use tracing::warn;
 
 use std::cmp;
 use std::collections::HashMap;
@@ -7,7 +8,7 @@ use std::num::ParseIntError;
 use std::path::Path;
 use std::time::Duration;
 
-use super::{parse_duration, ErrorKind, KeyValuePair, TypedResponseError};
+use super::{parse_duration, AudioFormat, ErrorKind, KeyValuePair, TypedResponseError};
 use crate::commands::{SongId, SongPosition};
 use crate::tag::Tag;
 
@@ -15,6 +16,7 @@ use crate::tag::Tag;
 ///
 /// [`playlistinfo`]: crate::commands::definitions::Queue
 #[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[non_exhaustive]
 pub struct SongInQueue {
     /// Position in queue.
@@ -31,6 +33,7 @@ pub struct SongInQueue {
 
 /// Range used when playing only part of a [`Song`].
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SongRange {
     /// Start playback at this timestamp.
     pub from: Duration,
@@ -43,7 +46,7 @@ impl SongInQueue {
         frame: impl IntoIterator<Item = KeyValuePair>,
         max_count: Option<usize>,
     ) -> Result<Vec<Self>, TypedResponseError> {
-        let max_count = max_count.unwrap_or(usize::max_value());
+        let max_count = max_count.unwrap_or(usize::MAX);
         assert!(max_count > 0);
 
         let mut fields = frame.into_iter().peekable();
@@ -81,6 +84,7 @@ impl SongInQueue {
 /// [playlist]: crate::commands::definitions::Queue
 /// [current song]: crate::commands::definitions::CurrentSong
 #[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[non_exhaustive]
 pub struct Song {
     /// Unique identifier of the song. May be a file path relative to the library root, or an URL
@@ -93,7 +97,7 @@ pub struct Song {
     /// Tags in this response.
     pub tags: HashMap<Tag, Vec<String>>,
     /// The `format` as returned by MPD.
-    pub format: Option<String>,
+    pub format: Option<AudioFormat>,
     /// Last modification date of the underlying file.
     pub last_modified: Option<DateTime<FixedOffset>>,
 }
@@ -105,6 +109,35 @@ impl Song {
         Path::new(&self.url)
     }
 
+    /// Get all values of the given tag, in the order MPD returned them.
+    ///
+    /// Returns an empty slice if the tag is not set on the song.
+    pub fn tag_values(&self, tag: &Tag) -> &[String] {
+        match self.tags.get(tag) {
+            Some(v) => v.as_slice(),
+            None => &[],
+        }
+    }
+
+    /// Get the first value of the given tag.
+    pub fn first(&self, tag: &Tag) -> Option<&str> {
+        match self.tag_values(tag) {
+            [] => None,
+            [v, ..] => Some(v),
+        }
+    }
+
+    /// Get all values of the given tag joined with `separator`, e.g. for displaying multiple
+    /// artists as `"Foo, Bar"`.
+    ///
+    /// Returns `None` if the tag is not set on the song.
+    pub fn joined(&self, tag: &Tag, separator: &str) -> Option<String> {
+        match self.tag_values(tag) {
+            [] => None,
+            values => Some(values.join(separator)),
+        }
+    }
+
     /// Get all artists of the song.
     pub fn artists(&self) -> &[String] {
         self.tag_values(&Tag::Artist)
@@ -117,29 +150,70 @@ impl Song {
 
     /// Get the album of the song.
     pub fn album(&self) -> Option<&str> {
-        self.single_tag_value(&Tag::Album)
+        self.first(&Tag::Album)
     }
 
     /// Get the title of the song.
     pub fn title(&self) -> Option<&str> {
-        self.single_tag_value(&Tag::Title)
+        self.first(&Tag::Title)
+    }
+
+    /// Get the title of the song for display purposes.
+    ///
+    /// Falls back to the stem of the file name if the song has no title tag, or to the full URL for
+    /// remote songs.
+    pub fn display_title(&self) -> &str {
+        if let Some(title) = self.title() {
+            return title;
+        }
+
+        if self.url.contains("://") {
+            return &self.url;
+        }
+
+        self.file_path()
+            .file_stem()
+            .and_then(|s| s.to_str())
+            .unwrap_or(&self.url)
+    }
+
+    /// Get the first artist of the song.
+    pub fn artist(&self) -> Option<&str> {
+        self.first(&Tag::Artist)
+    }
+
+    /// Get the first album artist of the song, falling back to the first artist if the song has
+    /// no album artist tag.
+    pub fn album_artist(&self) -> Option<&str> {
+        self.first(&Tag::AlbumArtist).or_else(|| self.artist())
+    }
+
+    /// Get the track number of the song.
+    ///
+    /// Values of the form `3/12` (track 3 of 12) are supported.
+    pub fn track(&self) -> Option<u64> {
+        parse_number(self.first(&Tag::Track))
+    }
+
+    /// Get the disc number of the song.
+    ///
+    /// Values of the form `1/2` (disc 1 of 2) are supported.
+    pub fn disc(&self) -> Option<u64> {
+        parse_number(self.first(&Tag::Disc))
     }
 
     /// Get the track and disc number of the song.
     ///
     /// If either are not set on the song, 0 is returned. This is a utility for sorting.
     pub fn number(&self) -> (u64, u64) {
-        let track = parse_number(self.single_tag_value(&Tag::Track));
-        let disc = parse_number(self.single_tag_value(&Tag::Disc));
-
-        (track, disc)
+        (self.track().unwrap_or(0), self.disc().unwrap_or(0))
     }
 
     pub(super) fn parse_frame(
         frame: impl IntoIterator<Item = KeyValuePair>,
         max_count: Option<usize>,
     ) -> Result<Vec<Self>, TypedResponseError> {
-        let max_count = max_count.unwrap_or(usize::max_value());
+        let max_count = max_count.unwrap_or(usize::MAX);
         assert!(max_count > 0);
 
         let mut fields = frame.into_iter().peekable();
@@ -161,27 +235,16 @@ impl Song {
             last_modified: None,
         }
     }
-
-    fn tag_values(&self, tag: &Tag) -> &[String] {
-        match self.tags.get(tag) {
-            Some(v) => v.as_slice(),
-            None => &[],
-        }
-    }
-
-    fn single_tag_value(&self, tag: &Tag) -> Option<&str> {
-        match self.tag_values(tag) {
-            [] => None,
-            [v, ..] => Some(v),
-        }
-    }
 }
 
-fn parse_number(val: Option<&str>) -> u64 {
-    match val {
-        None => 0,
-        Some(v) => v.parse().unwrap_or(0),
-    }
+fn parse_number(val: Option<&str>) -> Option<u64> {
+    let val = val?;
+    let number = match val.split_once('/') {
+        Some((number, _)) => number,
+        None => val,
+    };
+
+    number.trim().parse().ok()
 }
 
 struct SongIter<'a, I: Iterator> {
@@ -225,15 +288,10 @@ where
 
         let mut deprecated_time = None;
 
-        loop {
-            match self.fields.peek() {
-                Some((k, _)) => {
-                    // If the next key starts another file, the current iteration is done
-                    if let "file" | "directory" | "playlist" = &**k {
-                        break;
-                    }
-                }
-                None => break,
+        while let Some((k, _)) = self.fields.peek() {
+            // If the next key starts another file, the current iteration is done
+            if let "file" | "directory" | "playlist" = &**k {
+                break;
             }
 
             let (key, value) = self.fields.next().unwrap();
@@ -250,7 +308,11 @@ where
                     Ok(r) => range = Some(r),
                     Err(e) => return Some(Err(e)),
                 },
-                "Format" => song.format = Some(value),
+                // MPD may report formats this doesn't understand, which shouldn't fail the response
+                "Format" => match value.parse() {
+                    Ok(format) => song.format = Some(format),
+                    Err(_) => warn!(format = ?value, "failed to parse song format"),
+                },
                 "Last-Modified" => {
                     let ts = match DateTime::parse_from_rfc3339(&value) {
                         Ok(ts) => ts,
@@ -346,13 +408,7 @@ fn parse_field_error(field: &'static str, error: ParseIntError) -> TypedResponse
 #[cfg(test)]
 mod tests {
     use super::*;
-    use std::sync::Arc;
-
-    fn key_value_pairs(
-        raw: Vec<(&'static str, &'static str)>,
-    ) -> impl Iterator<Item = KeyValuePair> {
-        raw.into_iter().map(|(k, v)| (Arc::from(k), v.to_owned()))
-    }
+    use crate::commands::responses::tests::key_value_pairs;
 
     #[test]
     fn song_parser() {
@@ -385,6 +441,12 @@ mod tests {
             Some(DateTime::parse_from_rfc3339(ts).unwrap())
         );
         assert_eq!(songs[0].artists(), &["Foo", "Bar"]);
+        assert_eq!(songs[0].first(&Tag::Artist), Some("Foo"));
+        assert_eq!(
+            songs[0].joined(&Tag::Artist, ", ").as_deref(),
+            Some("Foo, Bar")
+        );
+        assert_eq!(songs[0].joined(&Tag::Genre, ", "), None);
         assert_eq!(songs[0].title(), None);
         assert_eq!(
             songs[0].tags.get(&Tag::Other("UnknownTag".into())),
@@ -393,11 +455,42 @@ mod tests {
 
         assert_eq!(songs[1].url, "foo/bar.baz");
         assert_eq!(songs[1].tags.len(), 0);
+        assert_eq!(songs[1].display_title(), "bar");
         assert_eq!(songs[1].duration, None);
         assert_eq!(songs[1].last_modified, None);
         assert_eq!(songs[1].format, None);
     }
 
+    #[test]
+    fn song_accessors() {
+        let input = key_value_pairs(vec![
+            ("file", "foo/bar.flac"),
+            ("Title", "Baz"),
+            ("Artist", "Foo"),
+            ("Artist", "Bar"),
+            ("Track", "3/12"),
+            ("Disc", "2"),
+        ]);
+
+        let song = Song::parse_frame(input, None).unwrap().remove(0);
+
+        assert_eq!(song.display_title(), "Baz");
+        assert_eq!(song.artist(), Some("Foo"));
+        assert_eq!(song.album_artist(), Some("Foo"));
+        assert_eq!(song.album(), None);
+        assert_eq!(song.track(), Some(3));
+        assert_eq!(song.disc(), Some(2));
+        assert_eq!(song.number(), (3, 2));
+
+        let song = Song::new(String::from("http://example.com/stream.mp3"));
+
+        assert_eq!(song.display_title(), "http://example.com/stream.mp3");
+        assert_eq!(song.artist(), None);
+        assert_eq!(song.album_artist(), None);
+        assert_eq!(song.track(), None);
+        assert_eq!(song.number(), (0, 0));
+    }
+
     #[test]
     fn song_parser_directory_with_modified() {
         // https://github.com/elomatreb/mpd_client/issues/7
@@ -477,6 +570,21 @@ mod tests {
         assert_eq!(err.field, "duration");
     }
 
+    #[test]
+    fn song_parser_format() {
+        let input = key_value_pairs(vec![
+            ("file", "foo.dsf"),
+            ("Format", "352800:dsd:2"),
+            ("file", "bar.flac"),
+            ("Format", "44100:s16le:2"),
+        ]);
+        let songs = Song::parse_frame(input, None).unwrap();
+
+        assert_eq!(songs[0].format, Some("dsd64:2".parse().unwrap()));
+        // An unknown format doesn't fail the whole response
+        assert_eq!(songs[1].format, None);
+    }
+
     #[test]
     fn song_parser_deprecated_time_field() {
         let input = key_value_pairs(vec![("file", "foo/bar.baz"), ("Time", "123")]);
@@ -0,0 +1,237 @@
+use super::{ErrorKind, KeyValuePair, TypedResponseError};
+use crate::commands::StickerType;
+use crate::tag::Tag;
+
+/// A sticker, as returned by the [`sticker get`] and [`sticker list`] commands.
+///
+/// [`sticker get`]: crate::commands::definitions::StickerGet
+/// [`sticker list`]: crate::commands::definitions::StickerList
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[non_exhaustive]
+pub struct Sticker {
+    /// Name of the sticker.
+    pub name: String,
+    /// Value of the sticker.
+    pub value: String,
+}
+
+/// An object with a matching sticker, as returned by the [`sticker find`] command.
+///
+/// [`sticker find`]: crate::commands::definitions::StickerFind
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[non_exhaustive]
+pub struct StickerMatch {
+    /// URI of the object the sticker is attached to.
+    pub uri: String,
+    /// Value of the sticker.
+    pub value: String,
+}
+
+/// A sticker name and the types of objects it is attached to, as returned by the
+/// [`stickernamestypes`] command.
+///
+/// [`stickernamestypes`]: crate::commands::definitions::StickerNamesTypes
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[non_exhaustive]
+pub struct StickerNameTypes {
+    /// Name of the sticker.
+    pub name: String,
+    /// The types of objects which have a sticker with this name.
+    pub types: Vec<StickerType>,
+}
+
+impl Sticker {
+    pub(super) fn parse(value: String) -> Result<Self, TypedResponseError> {
+        match value.split_once('=') {
+            Some((name, value)) => Ok(Sticker {
+                name: name.to_owned(),
+                value: value.to_owned(),
+            }),
+            None => Err(TypedResponseError {
+                field: "sticker",
+                kind: ErrorKind::InvalidValue(value),
+            }),
+        }
+    }
+
+    pub(super) fn parse_frame(
+        frame: impl IntoIterator<Item = KeyValuePair>,
+    ) -> Result<Vec<Self>, TypedResponseError> {
+        frame
+            .into_iter()
+            .map(|(key, value)| {
+                if &*key == "sticker" {
+                    Sticker::parse(value)
+                } else {
+                    Err(TypedResponseError {
+                        field: "sticker",
+                        kind: ErrorKind::UnexpectedField(key.as_ref().to_owned()),
+                    })
+                }
+            })
+            .collect()
+    }
+}
+
+impl StickerMatch {
+    pub(super) fn parse_frame(
+        frame: impl IntoIterator<Item = KeyValuePair>,
+    ) -> Result<Vec<Self>, TypedResponseError> {
+        let mut out = Vec::new();
+        let mut uri = None;
+
+        for (key, value) in frame {
+            if &*key != "sticker" {
+                // The key of the URI depends on the sticker type (e.g. `file` for songs)
+                uri = Some(value);
+                continue;
+            }
+
+            let uri = uri.take().ok_or(TypedResponseError {
+                field: "file",
+                kind: ErrorKind::Missing,
+            })?;
+
+            out.push(StickerMatch {
+                uri,
+                value: Sticker::parse(value)?.value,
+            });
+        }
+
+        Ok(out)
+    }
+}
+
+impl StickerNameTypes {
+    pub(super) fn parse_frame(
+        frame: impl IntoIterator<Item = KeyValuePair>,
+    ) -> Result<Vec<Self>, TypedResponseError> {
+        let mut out: Vec<StickerNameTypes> = Vec::new();
+
+        for (key, value) in frame {
+            match (&*key, out.last_mut()) {
+                ("name", _) => out.push(StickerNameTypes {
+                    name: value,
+                    types: Vec::new(),
+                }),
+                ("stickertype", Some(current)) => current.types.push(parse_sticker_type(value)?),
+                ("stickertype", None) => {
+                    return Err(TypedResponseError {
+                        field: "name",
+                        kind: ErrorKind::Missing,
+                    })
+                }
+                _ => {
+                    return Err(TypedResponseError {
+                        field: "stickertype",
+                        kind: ErrorKind::UnexpectedField(key.as_ref().to_owned()),
+                    })
+                }
+            }
+        }
+
+        Ok(out)
+    }
+}
+
+pub(super) fn parse_sticker_type(value: String) -> Result<StickerType, TypedResponseError> {
+    Ok(match value.as_str() {
+        "song" => StickerType::Song,
+        "playlist" => StickerType::Playlist,
+        _ => match Tag::try_from(value.as_str()) {
+            Ok(tag) => StickerType::Tag(tag),
+            Err(_) => {
+                return Err(TypedResponseError {
+                    field: "stickertype",
+                    kind: ErrorKind::InvalidValue(value),
+                })
+            }
+        },
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::responses::tests::key_value_pairs;
+
+    #[test]
+    fn stickers() {
+        let input = key_value_pairs(vec![("sticker", "rating=8"), ("sticker", "note=a=b")]);
+
+        assert_eq!(
+            Sticker::parse_frame(input),
+            Ok(vec![
+                Sticker {
+                    name: String::from("rating"),
+                    value: String::from("8"),
+                },
+                Sticker {
+                    name: String::from("note"),
+                    value: String::from("a=b"),
+                },
+            ])
+        );
+
+        let input = key_value_pairs(vec![("sticker", "invalid")]);
+        assert!(Sticker::parse_frame(input).is_err());
+    }
+
+    #[test]
+    fn sticker_matches() {
+        let input = key_value_pairs(vec![
+            ("file", "foo.flac"),
+            ("sticker", "rating=8"),
+            ("file", "bar.flac"),
+            ("sticker", "rating=2"),
+        ]);
+
+        assert_eq!(
+            StickerMatch::parse_frame(input),
+            Ok(vec![
+                StickerMatch {
+                    uri: String::from("foo.flac"),
+                    value: String::from("8"),
+                },
+                StickerMatch {
+                    uri: String::from("bar.flac"),
+                    value: String::from("2"),
+                },
+            ])
+        );
+
+        let input = key_value_pairs(vec![("sticker", "rating=8")]);
+        assert!(StickerMatch::parse_frame(input).is_err());
+    }
+
+    #[test]
+    fn sticker_names_types() {
+        let input = key_value_pairs(vec![
+            ("name", "rating"),
+            ("stickertype", "song"),
+            ("stickertype", "Album"),
+            ("name", "note"),
+            ("stickertype", "playlist"),
+        ]);
+
+        assert_eq!(
+            StickerNameTypes::parse_frame(input),
+            Ok(vec![
+                StickerNameTypes {
+                    name: String::from("rating"),
+                    types: vec![StickerType::Song, StickerType::Tag(Tag::Album)],
+                },
+                StickerNameTypes {
+                    name: String::from("note"),
+                    types: vec![StickerType::Playlist],
+                },
+            ])
+        );
+
+        let input = key_value_pairs(vec![("stickertype", "song")]);
+        assert!(StickerNameTypes::parse_frame(input).is_err());
+    }
+}
@@ -18,6 +18,9 @@ macro_rules! field {
 }
 
 macro_rules! parse {
+    (string, $value:ident, $field:literal) => {
+        $value
+    };
     (integer, $value:ident, $field:literal) => {
         $value.parse().map_err(|e| TypedResponseError {
             field: $field,
@@ -37,6 +40,19 @@ macro_rules! parse {
             }
         }
     };
+    (SingleMode, $value:ident, $field:literal) => {
+        match $value.as_str() {
+            "0" => SingleMode::Disabled,
+            "1" => SingleMode::Enabled,
+            "oneshot" => SingleMode::Oneshot,
+            _ => {
+                return Err(TypedResponseError {
+                    field: $field,
+                    kind: ErrorKind::InvalidValue($value),
+                })
+            }
+        }
+    };
     (boolean, $value:ident, $field:literal) => {
         match $value.as_str() {
             "1" => true,
@@ -49,6 +65,18 @@ macro_rules! parse {
             }
         }
     };
+    (timestamp, $value:ident, $field:literal) => {{
+        let seconds: i64 = parse!(integer, $value, $field);
+        match chrono::TimeZone::timestamp_opt(&chrono::Utc, seconds, 0).single() {
+            Some(timestamp) => timestamp,
+            None => {
+                return Err(TypedResponseError {
+                    field: $field,
+                    kind: ErrorKind::InvalidValue($value),
+                })
+            }
+        }
+    }};
     (duration, $value:ident, $field:literal) => {
         $crate::commands::responses::parse_duration($field, &$value)?
     };
@@ -11,6 +11,17 @@ use crate::raw::{ErrorResponse, Frame, MpdProtocolError};
 pub enum CommandError {
     /// The connection to MPD was closed cleanly
     ConnectionClosed,
+    /// The server closed the connection after the command was sent, without responding to it.
+    ///
+    /// This is the expected result of the [`Kill`](crate::commands::Kill) command, but may also
+    /// happen if the server crashes or is shut down while processing a command.
+    ClosedByServer,
+    /// The connection to MPD was lost, and the client is attempting to
+    /// [reconnect](crate::Client::connect_with_reconnect).
+    ///
+    /// Either the command was not sent, or the connection was lost before its response was
+    /// received, in which case the command may or may not have been executed.
+    Reconnecting,
     /// An underlying protocol error occurred, including IO errors
     Protocol(MpdProtocolError),
     /// Command returned an error
@@ -22,12 +33,34 @@ pub enum CommandError {
     },
     /// A [typed command](crate::commands) failed to convert its response.
     InvalidTypedResponse(TypedResponseError),
+    /// The server did not respond in time, see [`Client::set_command_timeout`].
+    ///
+    /// [`Client::set_command_timeout`]: crate::Client::set_command_timeout
+    Timeout,
+    /// The command requires a newer protocol version than the server supports. The command was not
+    /// sent.
+    UnsupportedByServer {
+        /// The protocol version required by the command, as `(major, minor, patch)`.
+        required: (u32, u32, u32),
+    },
 }
 
 impl fmt::Display for CommandError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
             CommandError::ConnectionClosed => write!(f, "the connection is closed"),
+            CommandError::ClosedByServer => {
+                write!(f, "the server closed the connection without responding")
+            }
+            CommandError::Reconnecting => write!(f, "the connection was lost, reconnecting"),
+            CommandError::Timeout => write!(f, "the command timed out"),
+            CommandError::UnsupportedByServer {
+                required: (major, minor, patch),
+            } => write!(
+                f,
+                "the command requires protocol version {}.{}.{}",
+                major, minor, patch
+            ),
             CommandError::Protocol(_) => write!(f, "protocol error"),
             CommandError::InvalidTypedResponse(_) => {
                 write!(f, "response was invalid for typed command")
@@ -39,7 +72,8 @@ impl fmt::Display for CommandError {
                 write!(
                     f,
                     "command returned an error [code {}]: {}",
-                    error.code, error.message,
+                    u64::from(error.code),
+                    error.message,
                 )?;
 
                 if !succesful_frames.is_empty() {
@@ -116,7 +150,8 @@ impl fmt::Display for StateChangeError {
             StateChangeError::ErrorMessage(ErrorResponse { code, message, .. }) => write!(
                 f,
                 "message contained an error frame [code {}]: {}",
-                code, message
+                u64::from(*code),
+                message
             ),
         }
     }
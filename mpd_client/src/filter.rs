@@ -1,11 +1,16 @@
 //! Tools for constructing [filter expressions], as used by e.g. the [`find`] command.
 //!
+//! Filters can be combined using `AND` (see [`Filter::and`], or the `&` operator) and negated
+//! (see [`Filter::negate`], or the `!` operator). MPD does not support an `OR` operator, so
+//! disjunctions need to be expressed using multiple commands.
+//!
 //! [`find`]: crate::commands::definitions::Find
 //! [filter expressions]: https://www.musicpd.org/doc/html/protocol.html#filters
 
 use std::borrow::Cow;
-use std::ops::Not;
+use std::ops::{BitAnd, Not};
 
+use chrono::{DateTime, TimeZone};
 use mpd_protocol::command::{escape_argument, Argument};
 
 use crate::Tag;
@@ -26,6 +31,14 @@ enum FilterType {
         operator: Operator,
         value: Cow<'static, str>,
     },
+    Base(Cow<'static, str>),
+    ModifiedSince(i64),
+    AddedSince(i64),
+    AudioFormat {
+        mask: bool,
+        value: Cow<'static, str>,
+    },
+    Priority(u8),
     Not(Box<FilterType>),
     And(Vec<FilterType>),
 }
@@ -80,6 +93,95 @@ impl Filter {
         Filter::new(tag, Operator::Equal, TAG_IS_ABSENT)
     }
 
+    /// Create a filter which selects songs inside the given directory (relative to the music
+    /// directory).
+    ///
+    /// ```
+    /// use mpd_protocol::command::Argument;
+    /// use mpd_client::Filter;
+    ///
+    /// assert_eq!(Filter::base("Artist/Album").render(), "(base \"Artist/Album\")");
+    /// ```
+    pub fn base(directory: impl Into<Cow<'static, str>>) -> Self {
+        Self(FilterType::Base(directory.into()))
+    }
+
+    /// Create a filter which selects songs whose files were modified after the given time.
+    ///
+    /// ```
+    /// use chrono::{TimeZone, Utc};
+    /// use mpd_protocol::command::Argument;
+    /// use mpd_client::Filter;
+    ///
+    /// assert_eq!(
+    ///     Filter::modified_since(Utc.timestamp_opt(1600000000, 0).unwrap()).render(),
+    ///     "(modified-since \"1600000000\")"
+    /// );
+    /// ```
+    pub fn modified_since<Tz: TimeZone>(time: DateTime<Tz>) -> Self {
+        Self(FilterType::ModifiedSince(time.timestamp()))
+    }
+
+    /// Create a filter which selects songs which were added to the database after the given time.
+    ///
+    /// **NOTE**: Supported on protocol versions 0.24 and later.
+    pub fn added_since<Tz: TimeZone>(time: DateTime<Tz>) -> Self {
+        Self(FilterType::AddedSince(time.timestamp()))
+    }
+
+    /// Create a filter which selects songs with exactly the given audio format, in the form
+    /// `samplerate:bits:channels`.
+    ///
+    /// ```
+    /// use mpd_protocol::command::Argument;
+    /// use mpd_client::Filter;
+    ///
+    /// assert_eq!(
+    ///     Filter::audio_format("44100:16:2").render(),
+    ///     "(AudioFormat == \"44100:16:2\")"
+    /// );
+    /// ```
+    pub fn audio_format(format: impl Into<Cow<'static, str>>) -> Self {
+        Self(FilterType::AudioFormat {
+            mask: false,
+            value: format.into(),
+        })
+    }
+
+    /// Create a filter which selects songs matching the given audio format mask, in the form
+    /// `samplerate:bits:channels`, where any component may be replaced with `*` to match any
+    /// value.
+    ///
+    /// ```
+    /// use mpd_protocol::command::Argument;
+    /// use mpd_client::Filter;
+    ///
+    /// assert_eq!(
+    ///     Filter::audio_format_mask("*:24:*").render(),
+    ///     "(AudioFormat =~ \"*:24:*\")"
+    /// );
+    /// ```
+    pub fn audio_format_mask(mask: impl Into<Cow<'static, str>>) -> Self {
+        Self(FilterType::AudioFormat {
+            mask: true,
+            value: mask.into(),
+        })
+    }
+
+    /// Create a filter which selects songs in the queue with a priority of at least `priority`.
+    ///
+    /// This is only meaningful for commands which operate on the queue.
+    ///
+    /// ```
+    /// use mpd_protocol::command::Argument;
+    /// use mpd_client::Filter;
+    ///
+    /// assert_eq!(Filter::priority_at_least(42).render(), "(prio >= 42)");
+    /// ```
+    pub fn priority_at_least(priority: u8) -> Self {
+        Self(FilterType::Priority(priority))
+    }
+
     /// Negate the filter.
     ///
     /// You can also use the negation operator (`!`) if you prefer to negate at the start of an
@@ -101,7 +203,7 @@ impl Filter {
 
     /// Chain the given filter onto this one with an `AND`.
     ///
-    /// Automatically flattens nested `AND` conditions.
+    /// Automatically flattens nested `AND` conditions. You can also use the `&` operator.
     ///
     /// ```
     /// use mpd_protocol::command::Argument;
@@ -135,6 +237,13 @@ impl Filter {
     }
 }
 
+impl Filter {
+    /// The minimum protocol version required by the expressions used in this filter.
+    pub(crate) fn min_protocol_version(&self) -> Option<(u32, u32, u32)> {
+        self.0.min_protocol_version()
+    }
+}
+
 impl Argument for Filter {
     fn render(self) -> Cow<'static, str> {
         Cow::Owned(self.0.render())
@@ -149,7 +258,29 @@ impl Not for Filter {
     }
 }
 
+impl BitAnd for Filter {
+    type Output = Self;
+
+    fn bitand(self, rhs: Self) -> Self::Output {
+        self.and(rhs)
+    }
+}
+
 impl FilterType {
+    fn min_protocol_version(&self) -> Option<(u32, u32, u32)> {
+        match self {
+            FilterType::Tag { operator, .. } => operator.min_protocol_version(),
+            FilterType::AddedSince(_) => Some((0, 24, 0)),
+            FilterType::Not(inner) => inner.min_protocol_version(),
+            FilterType::And(inner) => inner
+                .iter()
+                .map(FilterType::min_protocol_version)
+                .max()
+                .flatten(),
+            _ => None,
+        }
+    }
+
     fn render(self) -> String {
         match self {
             FilterType::Tag {
@@ -162,6 +293,17 @@ impl FilterType {
                 operator.as_str(),
                 escape_argument(&value)
             ),
+            FilterType::Base(directory) => format!("(base \"{}\")", escape_argument(&directory)),
+            FilterType::ModifiedSince(timestamp) => {
+                format!("(modified-since \"{}\")", timestamp)
+            }
+            FilterType::AddedSince(timestamp) => format!("(added-since \"{}\")", timestamp),
+            FilterType::AudioFormat { mask, value } => format!(
+                "(AudioFormat {} \"{}\")",
+                if mask { "=~" } else { "==" },
+                escape_argument(&value)
+            ),
+            FilterType::Priority(priority) => format!("(prio >= {})", priority),
             FilterType::Not(inner) => format!("(!{})", inner.render()),
             FilterType::And(inner) => {
                 assert!(inner.len() >= 2);
@@ -202,10 +344,38 @@ impl FilterType {
 pub enum Operator {
     /// Equality (`==`)
     Equal,
+    /// Case-sensitive equality (`eq_cs`)
+    ///
+    /// **NOTE**: Supported on protocol versions 0.24 and later.
+    EqualCaseSensitive,
+    /// Case-insensitive equality (`eq_ci`)
+    ///
+    /// **NOTE**: Supported on protocol versions 0.24 and later.
+    EqualCaseInsensitive,
     /// Negated equality (`!=`)
     NotEqual,
     /// Substring matching (`contains`)
     Contain,
+    /// Case-sensitive substring matching (`contains_cs`)
+    ///
+    /// **NOTE**: Supported on protocol versions 0.24 and later.
+    ContainCaseSensitive,
+    /// Case-insensitive substring matching (`contains_ci`)
+    ///
+    /// **NOTE**: Supported on protocol versions 0.24 and later.
+    ContainCaseInsensitive,
+    /// Prefix matching (`starts_with`)
+    ///
+    /// **NOTE**: Supported on protocol versions 0.24 and later.
+    StartsWith,
+    /// Case-sensitive prefix matching (`starts_with_cs`)
+    ///
+    /// **NOTE**: Supported on protocol versions 0.24 and later.
+    StartsWithCaseSensitive,
+    /// Case-insensitive prefix matching (`starts_with_ci`)
+    ///
+    /// **NOTE**: Supported on protocol versions 0.24 and later.
+    StartsWithCaseInsensitive,
     /// Perl-style regex matching (`=~`)
     Match,
     /// Negated Perl-style regex matching (`!~`)
@@ -213,11 +383,31 @@ pub enum Operator {
 }
 
 impl Operator {
+    fn min_protocol_version(&self) -> Option<(u32, u32, u32)> {
+        match self {
+            Operator::EqualCaseSensitive
+            | Operator::EqualCaseInsensitive
+            | Operator::ContainCaseSensitive
+            | Operator::ContainCaseInsensitive
+            | Operator::StartsWith
+            | Operator::StartsWithCaseSensitive
+            | Operator::StartsWithCaseInsensitive => Some((0, 24, 0)),
+            _ => None,
+        }
+    }
+
     fn as_str(&self) -> &'static str {
         match self {
             Operator::Equal => "==",
+            Operator::EqualCaseSensitive => "eq_cs",
+            Operator::EqualCaseInsensitive => "eq_ci",
             Operator::NotEqual => "!=",
             Operator::Contain => "contains",
+            Operator::ContainCaseSensitive => "contains_cs",
+            Operator::ContainCaseInsensitive => "contains_ci",
+            Operator::StartsWith => "starts_with",
+            Operator::StartsWithCaseSensitive => "starts_with_cs",
+            Operator::StartsWithCaseInsensitive => "starts_with_ci",
             Operator::Match => "=~",
             Operator::NotMatch => "!~",
         }
@@ -244,6 +434,41 @@ mod tests {
         );
     }
 
+    #[test]
+    fn filter_starts_with() {
+        assert_eq!(
+            Filter::new(Tag::Album, Operator::StartsWith, "Greatest").render(),
+            "(Album starts_with \"Greatest\")"
+        );
+    }
+
+    #[test]
+    fn filter_case_sensitivity() {
+        assert_eq!(
+            Filter::new(Tag::Artist, Operator::EqualCaseInsensitive, "foo").render(),
+            "(Artist eq_ci \"foo\")"
+        );
+        assert_eq!(
+            Filter::new(Tag::Title, Operator::ContainCaseSensitive, "Bar").render(),
+            "(Title contains_cs \"Bar\")"
+        );
+    }
+
+    #[test]
+    fn filter_special_predicates() {
+        assert_eq!(Filter::base("foo's dir").render(), "(base \"foo\\'s dir\")");
+        assert_eq!(
+            Filter::added_since(chrono::Utc.timestamp_opt(1600000000, 0).unwrap()).render(),
+            "(added-since \"1600000000\")"
+        );
+        assert_eq!(
+            Filter::base("foo")
+                .and(Filter::priority_at_least(10))
+                .render(),
+            "((base \"foo\") AND (prio >= 10))"
+        );
+    }
+
     #[test]
     fn filter_not() {
         assert_eq!(
@@ -274,4 +499,15 @@ mod tests {
             "((Artist == \"hello\") AND (Album == \"world\") AND (Title == \"foo\"))"
         );
     }
+
+    #[test]
+    fn filter_operators() {
+        let filter = !(Filter::tag(Tag::Artist, "hello") & Filter::tag(Tag::Album, "world"))
+            & Filter::tag(Tag::Title, "foo");
+
+        assert_eq!(
+            filter.render(),
+            "((!((Artist == \"hello\") AND (Album == \"world\"))) AND (Title == \"foo\"))"
+        );
+    }
 }
@@ -16,12 +16,18 @@
 mod client;
 mod errors;
 
+#[cfg(feature = "blocking")]
+pub mod blocking;
 pub mod commands;
 pub mod filter;
 pub mod state_changes;
 pub mod tag;
 
-pub use client::{Client, ConnectWithPasswordError, Connection};
+pub use client::{
+    AlbumArtStream, Client, ConnectWithPasswordError, Connection, ConnectionEvent,
+    ConnectionObserver, DisconnectReason, Messages, Rating, RatingError, Ratings, ReconnectOptions,
+    Socks5Proxy, StateTracker,
+};
 pub use errors::CommandError;
 pub use filter::Filter;
 pub use state_changes::Subsystem;
@@ -30,7 +36,7 @@ pub use tag::Tag;
 /// Protocol-level types.
 pub mod raw {
     pub use mpd_protocol::{
-        response::{Error as ErrorResponse, Frame},
+        response::{Error as ErrorResponse, ErrorCode, Frame},
         Command as RawCommand, CommandList as RawCommandList, MpdProtocolError,
     };
 }
@@ -48,18 +48,22 @@ pub enum Tag {
     Grouping,
     Label,
     Location,
+    Mood,
     Movement,
     MovementNumber,
     MusicBrainzArtistId,
     MusicBrainzRecordingId,
     MusicBrainzReleaseArtistId,
+    MusicBrainzReleaseGroupId,
     MusicBrainzReleaseId,
     MusicBrainzTrackId,
     MusicBrainzWorkId,
     Name,
     OriginalDate,
     Performer,
+    ShowMovement,
     Title,
+    TitleSort,
     Track,
     Work,
     /// Catch-all variant that contains the raw tag string when it doesn't match any other
@@ -95,18 +99,22 @@ impl Tag {
             Tag::Grouping => "Grouping",
             Tag::Label => "Label",
             Tag::Location => "Location",
+            Tag::Mood => "Mood",
             Tag::Movement => "Movement",
             Tag::MovementNumber => "MovementNumber",
             Tag::MusicBrainzArtistId => "MUSICBRAINZ_ARTISTID",
             Tag::MusicBrainzRecordingId => "MUSICBRAINZ_TRACKID",
             Tag::MusicBrainzReleaseArtistId => "MUSICBRAINZ_ALBUMARTISTID",
+            Tag::MusicBrainzReleaseGroupId => "MUSICBRAINZ_RELEASEGROUPID",
             Tag::MusicBrainzReleaseId => "MUSICBRAINZ_ALBUMID",
             Tag::MusicBrainzTrackId => "MUSICBRAINZ_RELEASETRACKID",
             Tag::MusicBrainzWorkId => "MUSICBRAINZ_WORKID",
             Tag::Name => "Name",
             Tag::OriginalDate => "OriginalDate",
             Tag::Performer => "Performer",
+            Tag::ShowMovement => "ShowMovement",
             Tag::Title => "Title",
+            Tag::TitleSort => "TitleSort",
             Tag::Track => "Track",
             Tag::Work => "Work",
         })
@@ -155,18 +163,22 @@ impl<'a> TryFrom<&'a str> for Tag {
             "Grouping" => Self::Grouping,
             "Label" => Self::Label,
             "Location" => Self::Location,
+            "Mood" => Self::Mood,
             "Movement" => Self::Movement,
             "MovementNumber" => Self::MovementNumber,
             "MUSICBRAINZ_ALBUMARTISTID" => Self::MusicBrainzReleaseArtistId,
             "MUSICBRAINZ_ALBUMID" => Self::MusicBrainzReleaseId,
             "MUSICBRAINZ_ARTISTID" => Self::MusicBrainzArtistId,
+            "MUSICBRAINZ_RELEASEGROUPID" => Self::MusicBrainzReleaseGroupId,
             "MUSICBRAINZ_RELEASETRACKID" => Self::MusicBrainzTrackId,
             "MUSICBRAINZ_TRACKID" => Self::MusicBrainzRecordingId,
             "MUSICBRAINZ_WORKID" => Self::MusicBrainzWorkId,
             "Name" => Self::Name,
             "OriginalDate" => Self::OriginalDate,
             "Performer" => Self::Performer,
+            "ShowMovement" => Self::ShowMovement,
             "Title" => Self::Title,
+            "TitleSort" => Self::TitleSort,
             "Track" => Self::Track,
             "Work" => Self::Work
         }
@@ -191,7 +203,7 @@ impl<'a> PartialEq<&'a str> for Tag {
 
 impl PartialOrd for Tag {
     fn partial_cmp(&self, other: &Tag) -> Option<std::cmp::Ordering> {
-        self.as_str().partial_cmp(&other.as_str())
+        Some(self.cmp(other))
     }
 }
 
@@ -213,6 +225,21 @@ impl Argument for Tag {
     }
 }
 
+#[cfg(feature = "serde")]
+impl serde::Serialize for Tag {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.as_str())
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Tag {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let raw = String::deserialize(deserializer)?;
+        Tag::try_from(raw.as_str()).map_err(serde::de::Error::custom)
+    }
+}
+
 /// Errors that may occur when attempting to create a [`Tag`].
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub enum TagError {
@@ -250,6 +277,10 @@ mod tests {
 
         // case-insensitive
         assert_eq!(Tag::try_from("artist"), Ok(Tag::Artist));
+        assert_eq!(
+            Tag::try_from("musicbrainz_releasegroupid"),
+            Ok(Tag::MusicBrainzReleaseGroupId)
+        );
 
         // unrecognized but valid tag
         assert_eq!(Tag::try_from("foo"), Ok(Tag::Other(Box::from("foo"))));
@@ -282,4 +313,18 @@ mod tests {
         assert_eq!(Tag::Artist, "Artist");
         assert_eq!(Tag::Other(Box::from("Foo")), "Foo");
     }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde() {
+        assert_eq!(
+            serde_json::to_string(&Tag::MusicBrainzRecordingId).unwrap(),
+            r#""MUSICBRAINZ_TRACKID""#
+        );
+        assert_eq!(
+            serde_json::from_str::<Tag>(r#""albumartist""#).unwrap(),
+            Tag::AlbumArtist
+        );
+        assert!(serde_json::from_str::<Tag>(r#""""#).is_err());
+    }
 }
@@ -1,16 +1,90 @@
 use criterion::{black_box, criterion_group, criterion_main, Criterion};
 use mpd_protocol::Connection;
 
-// NOTE: Benchmark requires `--cfg criterion` to be set to build correctly.
+use std::io::{self, Read};
 
-const LONG_RESPONSE: &[u8] = include_bytes!("long.response");
+/// Number of songs in the generated response, roughly corresponding to a large library.
+const SONGS: usize = 10_000;
+
+/// Generate a response resembling the output of `listallinfo` for a large library.
+fn long_response() -> Vec<u8> {
+    let mut out = Vec::new();
+
+    for i in 0..SONGS {
+        out.extend_from_slice(
+            format!(
+                "file: Artist {artist}/Album {album}/{i:02} - Title {i}.flac\n\
+                 Last-Modified: 2021-01-01T12:00:00Z\n\
+                 Format: 44100:16:2\n\
+                 Artist: Artist {artist}\n\
+                 AlbumArtist: Artist {artist}\n\
+                 Title: Title {i}\n\
+                 Album: Album {album}\n\
+                 Track: {i}\n\
+                 Date: 2021\n\
+                 Genre: Rock\n\
+                 Time: 241\n\
+                 duration: 240.667\n",
+                artist = i / 100,
+                album = i / 10,
+                i = i
+            )
+            .as_bytes(),
+        );
+    }
+
+    out.extend_from_slice(b"OK\n");
+    out
+}
+
+/// Generate a response containing a single large binary blob, as returned by `albumart`.
+fn binary_response() -> Vec<u8> {
+    let data = vec![b'\n'; 1024 * 1024];
+    let mut out = format!("size: {0}\nbinary: {0}\n", data.len()).into_bytes();
+
+    out.extend_from_slice(&data);
+    out.extend_from_slice(b"\nOK\n");
+    out
+}
+
+/// Reader that returns at most `chunk_size` bytes per read, simulating a slow connection.
+struct ChunkedReader<'a> {
+    data: &'a [u8],
+    chunk_size: usize,
+}
+
+impl Read for ChunkedReader<'_> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let len = buf.len().min(self.chunk_size);
+        (&mut self.data).take(len as u64).read(buf)
+    }
+}
+
+fn receive(response: &[u8], chunk_size: usize) {
+    let io = ChunkedReader {
+        data: response,
+        chunk_size,
+    };
+    let io = (&b"OK MPD 0.23.5\n"[..]).chain(io);
+
+    let mut connection = Connection::connect(io).unwrap();
+    let _ = connection.receive().unwrap();
+}
 
 pub fn criterion_benchmark(c: &mut Criterion) {
+    let response = long_response();
+    let binary = binary_response();
+
     c.bench_function("long response", |b| {
-        b.iter(|| {
-            let mut connection = Connection::new_internal(black_box(LONG_RESPONSE));
-            let _ = connection.receive().unwrap();
-        })
+        b.iter(|| receive(black_box(&response), usize::MAX))
+    });
+
+    c.bench_function("long response, small reads", |b| {
+        b.iter(|| receive(black_box(&response), 64))
+    });
+
+    c.bench_function("binary response, small reads", |b| {
+        b.iter(|| receive(black_box(&binary), 1024))
     });
 }
 
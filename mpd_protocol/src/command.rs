@@ -97,8 +97,9 @@ impl Command {
     ///
     /// # Errors
     ///
-    /// Errors are returned when the argument is invalid (e.g. empty string or containing invalid
-    /// characters such as newlines).
+    /// Errors are returned when the argument is invalid (e.g. containing leading or trailing
+    /// whitespace or invalid characters such as newlines). Empty arguments are valid and are
+    /// rendered as an empty quoted string.
     pub fn add_argument(&mut self, argument: impl Argument) -> Result<(), CommandError> {
         let argument = argument.render();
 
@@ -247,6 +248,20 @@ impl Argument for Duration {
     }
 }
 
+macro_rules! impl_integer_argument {
+    ($($ty:ty),+) => {
+        $(
+            impl Argument for $ty {
+                fn render(self) -> Cow<'static, str> {
+                    Cow::Owned(self.to_string())
+                }
+            }
+        )+
+    };
+}
+
+impl_integer_argument!(u8, u16, u32, u64, usize, i8, i16, i32, i64, isize);
+
 /// Escape a single argument, prefixing necessary characters (quotes and backslashes) with
 /// backslashes.
 ///
@@ -262,7 +277,8 @@ pub fn escape_argument(argument: &str) -> Cow<'_, str> {
 
 /// Like escape_argument, but preserves the lifetime of a passed Cow and can quote if necessary
 fn escape_argument_internal(argument: Cow<'_, str>, enable_quotes: bool) -> Cow<'_, str> {
-    let needs_quotes = enable_quotes && argument.contains(&[' ', '\t'][..]);
+    let needs_quotes =
+        enable_quotes && (argument.is_empty() || argument.contains(&[' ', '\t'][..]));
     let escape_count = argument.chars().filter(|c| should_escape(*c)).count();
 
     if escape_count == 0 && !needs_quotes {
@@ -301,8 +317,8 @@ fn should_escape(c: char) -> bool {
 
 fn validate_no_extra_whitespace(command: &str) -> Result<(), CommandError> {
     // If either the first or last character are whitespace we have leading or trailing whitespace
-    if command.chars().next().unwrap().is_ascii_whitespace()
-        || command.chars().next_back().unwrap().is_ascii_whitespace()
+    if command.starts_with(|c: char| c.is_ascii_whitespace())
+        || command.ends_with(|c: char| c.is_ascii_whitespace())
     {
         Err(CommandError::UnncessaryWhitespace)
     } else {
@@ -329,7 +345,7 @@ fn validate_command_part(command: &str) -> Result<(), CommandError> {
     }
 }
 
-/// Validate a
+/// Validate a command argument.
 fn validate_argument(argument: &str) -> Result<&str, CommandError> {
     validate_no_extra_whitespace(argument)?;
 
@@ -388,6 +404,20 @@ mod test {
         assert_eq!(buf, "hello \"foo\\'s bar\\\"\"\n");
         buf.clear();
 
+        Command::new("lsinfo").argument("").render(buf);
+        assert_eq!(buf, "lsinfo \"\"\n");
+        buf.clear();
+
+        assert_eq!(
+            Command::new("hello").add_argument("foo\nbar"),
+            Err(CommandError::InvalidCharacter(3, '\n'))
+        );
+
+        assert_eq!(
+            Command::new("hello").add_argument("foo "),
+            Err(CommandError::UnncessaryWhitespace)
+        );
+
         assert_eq!(
             Command::build(" hello").unwrap_err(),
             CommandError::UnncessaryWhitespace
@@ -439,6 +469,10 @@ mod test {
         assert_eq!(true.render(), "1");
         assert_eq!(false.render(), "0");
 
+        assert_eq!(5u8.render(), "5");
+        assert_eq!(1234usize.render(), "1234");
+        assert_eq!((-3i32).render(), "-3");
+
         assert_eq!(Duration::from_secs(2).render(), "2.000");
         assert_eq!(Duration::from_secs_f64(2.34567).render(), "2.346");
     }
@@ -1,4 +1,4 @@
-use bytes::BytesMut;
+use bytes::{Buf, BytesMut};
 use tracing::{debug, error, info, trace};
 
 #[cfg(feature = "async")]
@@ -8,7 +8,7 @@ use std::io::{self, Read, Write};
 
 use crate::{
     parser,
-    response::{ResponseBuilder, ResponseFieldCache},
+    response::{ResponseBuilder, ResponseChunk, ResponseFieldCache},
     Command, CommandList, MpdProtocolError, Response,
 };
 
@@ -24,10 +24,16 @@ pub struct Connection<IO> {
     recv_buf: BytesMut,
     total_received: usize,
     send_buf: BytesMut,
+    partial_in_progress: bool,
+    max_response_size: Option<usize>,
+    lossy_utf8: bool,
+    resync_pending: bool,
+    /// Length of the binary payload which remains to be skipped while resynchronizing.
+    resync_binary: usize,
 }
 
 impl<IO> Connection<IO> {
-    #[cfg(any(fuzzing, criterion))]
+    #[cfg(fuzzing)]
     #[allow(dead_code)]
     #[doc(hidden)]
     pub fn new_internal(io: IO) -> Connection<IO> {
@@ -41,6 +47,11 @@ impl<IO> Connection<IO> {
             recv_buf,
             total_received: 0,
             send_buf: BytesMut::new(),
+            partial_in_progress: false,
+            max_response_size: None,
+            lossy_utf8: false,
+            resync_pending: false,
+            resync_binary: 0,
         }
     }
 
@@ -87,6 +98,11 @@ impl<IO> Connection<IO> {
             recv_buf,
             total_received: 0,
             send_buf: BytesMut::new(),
+            partial_in_progress: false,
+            max_response_size: None,
+            lossy_utf8: false,
+            resync_pending: false,
+            resync_binary: 0,
         })
     }
 
@@ -140,37 +156,61 @@ impl<IO> Connection<IO> {
     ///  - Reading from the given IO resource returns an error
     ///  - Malformed response data is received
     ///  - The connection is closed while a response is in progress
+    ///
+    /// After malformed response data was received, the remainder of the invalid response is
+    /// skipped, so that the connection may continue to be used for further commands.
     #[tracing::instrument(skip(self), err)]
     pub fn receive(&mut self) -> Result<Option<Response>, MpdProtocolError>
     where
         IO: Read,
     {
         let mut response_builder = ResponseBuilder::new(&mut self.field_cache);
+        response_builder.set_max_len(self.max_response_size);
+        response_builder.set_lossy(self.lossy_utf8);
 
         loop {
-            // Split off the read part of the receive buffer
-            let buf_size = self.recv_buf.len();
-            let remaining = self.recv_buf.split_off(self.total_received);
-
-            // Try to parse response data from the initialized section of the buffer, removing the
-            // consumed parts from the buffer
-            let maybe_parsed = response_builder.parse(&mut self.recv_buf)?;
-
-            // Update the length of the initialized section to the remaining length
-            self.total_received = self.recv_buf.len();
-
-            // Join back the remaining data with the main buffer, and readjust the length
-            self.recv_buf.unsplit(remaining);
-            self.recv_buf.resize(buf_size, 0);
-
-            if let Some(response) = maybe_parsed {
-                debug!(
-                    frames = response.successful_frames(),
-                    error = response.is_error(),
-                    fields = response.field_count(),
-                    "received complete response"
+            if self.resync_pending {
+                self.resync_pending = !discard_until_terminator(
+                    &mut self.recv_buf,
+                    &mut self.total_received,
+                    &mut self.resync_binary,
                 );
-                break Ok(Some(response));
+            }
+
+            if !self.resync_pending {
+                // Split off the read part of the receive buffer
+                let buf_size = self.recv_buf.len();
+                let remaining = self.recv_buf.split_off(self.total_received);
+
+                // Try to parse response data from the initialized section of the buffer, removing
+                // the consumed parts from the buffer
+                let maybe_parsed = response_builder.parse(&mut self.recv_buf);
+
+                // Update the length of the initialized section to the remaining length
+                self.total_received = self.recv_buf.len();
+
+                // Join back the remaining data with the main buffer, and readjust the length
+                self.recv_buf.unsplit(remaining);
+                self.recv_buf.resize(buf_size, 0);
+
+                match maybe_parsed {
+                    Ok(Some(response)) => {
+                        debug!(
+                            frames = response.successful_frames(),
+                            error = response.is_error(),
+                            fields = response.field_count(),
+                            "received complete response"
+                        );
+                        self.partial_in_progress = false;
+                        break Ok(Some(response));
+                    }
+                    Ok(None) => {}
+                    Err(e) => {
+                        self.resync_pending = true;
+                        self.partial_in_progress = false;
+                        break Err(e);
+                    }
+                }
             }
 
             let (_, amount_read) =
@@ -191,6 +231,82 @@ impl<IO> Connection<IO> {
         }
     }
 
+    /// Receive a part of a response from the server.
+    ///
+    /// Unlike [`Connection::receive`], this does not wait for the complete response to arrive
+    /// before returning, which avoids buffering very large responses (e.g. to `listallinfo`) in
+    /// their entirety. Instead, the fields received so far are returned as soon as they are
+    /// available, followed by the markers ending frames and the response. The response is
+    /// complete once [`ResponseChunk::EndOfResponse`] or [`ResponseChunk::Error`] is returned.
+    ///
+    /// This will return `Ok(None)` if the connection is closed cleanly.
+    ///
+    /// # Errors
+    ///
+    /// This will return an error in the same conditions as [`Connection::receive`].
+    #[tracing::instrument(skip(self), err)]
+    pub fn receive_partial(&mut self) -> Result<Option<ResponseChunk>, MpdProtocolError>
+    where
+        IO: Read,
+    {
+        let mut response_builder = ResponseBuilder::new(&mut self.field_cache);
+        response_builder.set_max_len(self.max_response_size);
+        response_builder.set_lossy(self.lossy_utf8);
+
+        loop {
+            if self.resync_pending {
+                self.resync_pending = !discard_until_terminator(
+                    &mut self.recv_buf,
+                    &mut self.total_received,
+                    &mut self.resync_binary,
+                );
+            }
+
+            if !self.resync_pending {
+                // See `Connection::receive` for the handling of the buffer
+                let buf_size = self.recv_buf.len();
+                let remaining = self.recv_buf.split_off(self.total_received);
+
+                let maybe_parsed = response_builder.parse_chunk(&mut self.recv_buf);
+
+                self.total_received = self.recv_buf.len();
+
+                self.recv_buf.unsplit(remaining);
+                self.recv_buf.resize(buf_size, 0);
+
+                match maybe_parsed {
+                    Ok(Some(chunk)) => {
+                        trace!(?chunk, "received response chunk");
+                        self.partial_in_progress = !chunk.is_end_of_response();
+                        break Ok(Some(chunk));
+                    }
+                    Ok(None) => {}
+                    Err(e) => {
+                        self.resync_pending = true;
+                        self.partial_in_progress = false;
+                        break Err(e);
+                    }
+                }
+            }
+
+            let (_, amount_read) =
+                read_to_buffer(&mut self.io, &mut self.recv_buf, &mut self.total_received)?;
+
+            if amount_read == 0 {
+                if self.partial_in_progress || self.total_received != 0 {
+                    error!("EOF while receiving response");
+                    break Err(MpdProtocolError::Io(io::Error::new(
+                        io::ErrorKind::UnexpectedEof,
+                        "unexpected end of file while receiving response",
+                    )));
+                } else {
+                    debug!("clean EOF while receiving response");
+                    break Ok(None);
+                }
+            }
+        }
+    }
+
     /// Send a command and receive its response.
     ///
     /// This is essentially a shorthand for [`Connection::send`] followed by [`Connection::receive`].
@@ -249,6 +365,29 @@ impl<IO> Connection<IO> {
         }
     }
 
+    /// Set the maximum size of a response, in bytes.
+    ///
+    /// If the server sends a response exceeding this size, receiving it will fail with
+    /// [`MpdProtocolError::ResponseTooLarge`] instead of buffering an unbounded amount of data.
+    /// With [`Connection::receive_partial`], this limits the amount of data buffered to produce a
+    /// single chunk instead. Pass `None` to disable the limit, which is the default.
+    ///
+    /// As with malformed responses, the remainder of a response which exceeded the limit is
+    /// skipped after the error is returned.
+    pub fn set_max_response_size(&mut self, limit: Option<usize>) {
+        self.max_response_size = limit;
+    }
+
+    /// Enable or disable lossy decoding of field values.
+    ///
+    /// By default, a field value which is not valid UTF-8 (e.g. due to a broken tag in the
+    /// database) makes receiving the response fail with [`MpdProtocolError::InvalidMessage`].
+    /// When lossy decoding is enabled, invalid sequences are replaced with
+    /// [`U+FFFD REPLACEMENT CHARACTER`][char::REPLACEMENT_CHARACTER] instead.
+    pub fn set_lossy_utf8(&mut self, lossy: bool) {
+        self.lossy_utf8 = lossy;
+    }
+
     /// Returns the protocol version the server is using.
     pub fn protocol_version(&self) -> &str {
         &self.protocol_version
@@ -277,6 +416,21 @@ fn read_to_buffer<'a, R: Read>(
     Ok((&buf[..*total], read))
 }
 
+/// Discard data from the start of `buf` up to and including the next response terminator (an `OK`
+/// or `ACK` line), where `len` is the length of the initialized part of the buffer.
+///
+/// Returns `true` if a terminator was found. Otherwise, only complete lines (and binary payloads)
+/// are discarded.
+fn discard_until_terminator(buf: &mut BytesMut, len: &mut usize, binary: &mut usize) -> bool {
+    let (discard, found) = parser::skip_to_terminator(&buf[..*len], binary);
+    trace!(discard, found, "discarding data after invalid response");
+
+    buf.advance(discard);
+    *len -= discard;
+
+    found
+}
+
 /// An **asynchronous** connection to an MPD server.
 #[cfg(feature = "async")]
 #[cfg_attr(docsrs, doc(cfg(feature = "async")))]
@@ -338,6 +492,11 @@ impl<IO> AsyncConnection<IO> {
             recv_buf,
             total_received: 0,
             send_buf: BytesMut::new(),
+            partial_in_progress: false,
+            max_response_size: None,
+            lossy_utf8: false,
+            resync_pending: false,
+            resync_binary: 0,
         }))
     }
 
@@ -400,16 +559,38 @@ impl<IO> AsyncConnection<IO> {
         IO: AsyncRead + Unpin,
     {
         let mut response_builder = ResponseBuilder::new(&mut self.0.field_cache);
+        response_builder.set_max_len(self.0.max_response_size);
+        response_builder.set_lossy(self.0.lossy_utf8);
 
         loop {
-            if let Some(response) = response_builder.parse(&mut self.0.recv_buf)? {
-                debug!(
-                    frames = response.successful_frames(),
-                    fields = response.field_count(),
-                    error = response.is_error(),
-                    "received complete response"
+            if self.0.resync_pending {
+                let mut len = self.0.recv_buf.len();
+                self.0.resync_pending = !discard_until_terminator(
+                    &mut self.0.recv_buf,
+                    &mut len,
+                    &mut self.0.resync_binary,
                 );
-                break Ok(Some(response));
+            }
+
+            if !self.0.resync_pending {
+                match response_builder.parse(&mut self.0.recv_buf) {
+                    Ok(Some(response)) => {
+                        debug!(
+                            frames = response.successful_frames(),
+                            fields = response.field_count(),
+                            error = response.is_error(),
+                            "received complete response"
+                        );
+                        self.0.partial_in_progress = false;
+                        break Ok(Some(response));
+                    }
+                    Ok(None) => {}
+                    Err(e) => {
+                        self.0.resync_pending = true;
+                        self.0.partial_in_progress = false;
+                        break Err(e);
+                    }
+                }
             }
 
             let read = self.0.io.read_buf(&mut self.0.recv_buf).await?;
@@ -430,6 +611,67 @@ impl<IO> AsyncConnection<IO> {
         }
     }
 
+    /// Receive a part of a response from the server.
+    ///
+    /// See [`Connection::receive_partial`] for details.
+    ///
+    /// # Errors
+    ///
+    /// This will return an error in the same conditions as [`AsyncConnection::receive`].
+    #[cfg_attr(docsrs, doc(cfg(feature = "async")))]
+    #[tracing::instrument(skip(self), err)]
+    pub async fn receive_partial(&mut self) -> Result<Option<ResponseChunk>, MpdProtocolError>
+    where
+        IO: AsyncRead + Unpin,
+    {
+        let mut response_builder = ResponseBuilder::new(&mut self.0.field_cache);
+        response_builder.set_max_len(self.0.max_response_size);
+        response_builder.set_lossy(self.0.lossy_utf8);
+
+        loop {
+            if self.0.resync_pending {
+                let mut len = self.0.recv_buf.len();
+                self.0.resync_pending = !discard_until_terminator(
+                    &mut self.0.recv_buf,
+                    &mut len,
+                    &mut self.0.resync_binary,
+                );
+            }
+
+            if !self.0.resync_pending {
+                match response_builder.parse_chunk(&mut self.0.recv_buf) {
+                    Ok(Some(chunk)) => {
+                        trace!(?chunk, "received response chunk");
+                        self.0.partial_in_progress = !chunk.is_end_of_response();
+                        break Ok(Some(chunk));
+                    }
+                    Ok(None) => {}
+                    Err(e) => {
+                        self.0.resync_pending = true;
+                        self.0.partial_in_progress = false;
+                        break Err(e);
+                    }
+                }
+            }
+
+            let read = self.0.io.read_buf(&mut self.0.recv_buf).await?;
+            trace!(read);
+
+            if read == 0 {
+                if self.0.partial_in_progress || !self.0.recv_buf.is_empty() {
+                    error!("EOF while receiving response");
+                    break Err(MpdProtocolError::Io(io::Error::new(
+                        io::ErrorKind::UnexpectedEof,
+                        "unexpected end of file while receiving response",
+                    )));
+                } else {
+                    debug!("clean EOF while receiving");
+                    break Ok(None);
+                }
+            }
+        }
+    }
+
     /// Send a command and receive its response.
     ///
     /// This is essentially a shorthand for [`AsyncConnection::send`] followed by
@@ -493,6 +735,20 @@ impl<IO> AsyncConnection<IO> {
         }
     }
 
+    /// Set the maximum size of a response, in bytes.
+    ///
+    /// See [`Connection::set_max_response_size`] for details.
+    pub fn set_max_response_size(&mut self, limit: Option<usize>) {
+        self.0.max_response_size = limit;
+    }
+
+    /// Enable or disable lossy decoding of field values.
+    ///
+    /// See [`Connection::set_lossy_utf8`] for details.
+    pub fn set_lossy_utf8(&mut self, lossy: bool) {
+        self.0.lossy_utf8 = lossy;
+    }
+
     /// Returns the protocol version the server is using.
     pub fn protocol_version(&self) -> &str {
         &self.0.protocol_version
@@ -520,6 +776,11 @@ mod tests_sync {
             recv_buf,
             total_received: 0,
             send_buf: BytesMut::new(),
+            partial_in_progress: false,
+            max_response_size: None,
+            lossy_utf8: false,
+            resync_pending: false,
+            resync_binary: 0,
         }
     }
 
@@ -593,6 +854,140 @@ mod tests_sync {
 
         assert_matches!(response, Err(MpdProtocolError::Io(e)) if e.kind() == io::ErrorKind::UnexpectedEof);
     }
+
+    #[test]
+    fn receive_too_large() {
+        let io = Read::chain(&b"foo: bar\nhello: "[..], &b"world\nOK\n"[..]);
+        let mut connection = new_conn(io);
+        connection.set_max_response_size(Some(16));
+
+        assert_matches!(
+            connection.receive(),
+            Err(MpdProtocolError::ResponseTooLarge)
+        );
+    }
+
+    #[test]
+    fn receive_lossy() {
+        let io: &[u8] = b"foo: b\xffr\nOK\n";
+        let mut connection = new_conn(io);
+
+        assert_matches!(connection.receive(), Err(MpdProtocolError::InvalidMessage));
+
+        let io: &[u8] = b"foo: b\xffr\nOK\n";
+        let mut connection = new_conn(io);
+        connection.set_lossy_utf8(true);
+
+        let response = connection.receive().unwrap().unwrap();
+        assert_eq!(
+            response.single_frame().unwrap().find("foo"),
+            Some("b\u{FFFD}r")
+        );
+    }
+
+    #[test]
+    fn receive_resync() {
+        let io = Read::chain(
+            &b"foo: bar\ninvalid\nfoo: "[..],
+            &b"bar\nOK\nhello: world\nOK\n"[..],
+        );
+        let mut connection = new_conn(io);
+
+        assert_matches!(connection.receive(), Err(MpdProtocolError::InvalidMessage));
+
+        let response = connection.receive().unwrap().unwrap();
+        assert_eq!(
+            response.single_frame().unwrap().find("hello"),
+            Some("world")
+        );
+
+        assert_matches!(connection.receive(), Ok(None));
+    }
+
+    #[test]
+    fn receive_resync_binary() {
+        let io = Read::chain(
+            &b"size: 22\nbinary: 22\nOK\nACK "[..],
+            &b"[5@0] {} x\nfoo\n\nOK\nhello: world\nOK\n"[..],
+        );
+        let mut connection = new_conn(io);
+        connection.set_max_response_size(Some(16));
+
+        assert_matches!(
+            connection.receive(),
+            Err(MpdProtocolError::ResponseTooLarge)
+        );
+
+        let response = connection.receive().unwrap().unwrap();
+        assert_eq!(
+            response.single_frame().unwrap().find("hello"),
+            Some("world")
+        );
+
+        assert_matches!(connection.receive(), Ok(None));
+    }
+
+    #[test]
+    fn receive_partial() {
+        let io = Read::chain(
+            &b"foo: bar\nlist_"[..],
+            &b"OK\nhello: world\nlist_OK\nOK\n"[..],
+        );
+        let mut connection = new_conn(io);
+
+        assert_matches!(
+            connection.receive_partial(),
+            Ok(Some(ResponseChunk::Fields(f))) if f.find("foo") == Some("bar")
+        );
+        assert_matches!(
+            connection.receive_partial(),
+            Ok(Some(ResponseChunk::EndOfFrame))
+        );
+        assert_matches!(
+            connection.receive_partial(),
+            Ok(Some(ResponseChunk::Fields(f))) if f.find("hello") == Some("world")
+        );
+        assert_matches!(
+            connection.receive_partial(),
+            Ok(Some(ResponseChunk::EndOfFrame))
+        );
+        assert_matches!(
+            connection.receive_partial(),
+            Ok(Some(ResponseChunk::EndOfResponse))
+        );
+        assert_matches!(connection.receive_partial(), Ok(None));
+    }
+
+    #[test]
+    fn receive_partial_eof() {
+        let io: &[u8] = b"foo: bar\n";
+        let mut connection = new_conn(io);
+
+        assert_matches!(
+            connection.receive_partial(),
+            Ok(Some(ResponseChunk::Fields(_)))
+        );
+        assert_matches!(
+            connection.receive_partial(),
+            Err(MpdProtocolError::Io(e)) if e.kind() == io::ErrorKind::UnexpectedEof
+        );
+    }
+
+    #[test]
+    fn receive_binary_larger_than_buffer() {
+        let data = vec![b'\n'; DEFAULT_BUFFER_CAPACITY * 3];
+
+        let mut io = format!("size: {0}\nbinary: {0}\n", data.len()).into_bytes();
+        io.extend_from_slice(&data);
+        io.extend_from_slice(b"\nOK\n");
+
+        let mut connection = new_conn(&io[..]);
+
+        let response = connection.receive().unwrap().unwrap();
+        let mut frame = response.single_frame().unwrap();
+
+        assert_eq!(frame.get_binary().as_deref(), Some(&data[..]));
+    }
 }
 
 #[cfg(test)]
@@ -611,6 +1006,11 @@ mod tests_async {
             recv_buf: BytesMut::new(),
             total_received: 0,
             send_buf: BytesMut::new(),
+            partial_in_progress: false,
+            max_response_size: None,
+            lossy_utf8: false,
+            resync_pending: false,
+            resync_binary: 0,
         })
     }
 
@@ -698,6 +1098,44 @@ mod tests_async {
         assert_matches!(response, Some(response) if response.is_success());
     }
 
+    #[tokio::test]
+    async fn receive_partial() {
+        let io = MockBuilder::new()
+            .read(b"foo: bar\n")
+            .read(b"hello: world\nOK")
+            .read(b"\n")
+            .build();
+        let mut connection = new_conn(io);
+
+        let chunk = connection.receive_partial().await.unwrap();
+        assert_matches!(chunk, Some(ResponseChunk::Fields(f)) if f.fields_len() == 1);
+
+        let chunk = connection.receive_partial().await.unwrap();
+        assert_matches!(chunk, Some(ResponseChunk::Fields(f)) if f.find("hello") == Some("world"));
+
+        let chunk = connection.receive_partial().await.unwrap();
+        assert_matches!(chunk, Some(ResponseChunk::EndOfResponse));
+
+        let chunk = connection.receive_partial().await.unwrap();
+        assert_matches!(chunk, None);
+    }
+
+    #[tokio::test]
+    async fn receive_binary() {
+        let io = MockBuilder::new()
+            .read(b"size: 12\ntype: image/png\nbinary: 6\nOK\n")
+            .read(b"\0\n\n\nOK\n")
+            .build();
+        let mut connection = new_conn(io);
+
+        let response = connection.receive().await.unwrap().unwrap();
+        let mut frame = response.single_frame().unwrap();
+
+        assert_eq!(frame.find("size"), Some("12"));
+        assert_eq!(frame.find("type"), Some("image/png"));
+        assert_eq!(frame.get_binary().as_deref(), Some(&b"OK\n\0\n\n"[..]));
+    }
+
     #[tokio::test]
     async fn receive_eof_clean() {
         let io = MockBuilder::new().build();
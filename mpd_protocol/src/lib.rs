@@ -16,6 +16,36 @@
 //! |---------|---------------------------------|
 //! | `async` | Async support, based on [Tokio] |
 //!
+//! # Example
+//!
+//! Commands are rendered to the wire format (including quoting and escaping of arguments) by the
+//! connection types, so you never need to write to the underlying IO resource directly.
+//!
+//! ```no_run
+//! use mpd_protocol::{Command, CommandList, Connection};
+//! use std::net::TcpStream;
+//!
+//! # fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! let stream = TcpStream::connect("localhost:6600")?;
+//! let mut connection = Connection::connect(stream)?;
+//!
+//! // A single command
+//! let response = connection.command(Command::new("status"))?;
+//! println!("{:?}", response.single_frame());
+//!
+//! // A command list, the responses to the commands are separated into individual frames
+//! let list = CommandList::new(Command::new("status"))
+//!     .command(Command::new("find").argument("(Artist == \"Foo Bar\")"));
+//!
+//! for frame in connection.command_list(list)? {
+//!     println!("{:?}", frame);
+//! }
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! With the `async` feature enabled, `AsyncConnection` offers the same methods for use with [Tokio].
+//!
 //! [MPD]: https://musicpd.org
 //! [Tokio]: https://tokio.rs
 
@@ -44,6 +74,8 @@ pub enum MpdProtocolError {
     Io(io::Error),
     /// A message could not be parsed successfully.
     InvalidMessage,
+    /// A response exceeded the configured maximum size.
+    ResponseTooLarge,
 }
 
 impl fmt::Display for MpdProtocolError {
@@ -51,6 +83,7 @@ impl fmt::Display for MpdProtocolError {
         match self {
             MpdProtocolError::Io(_) => write!(f, "IO error"),
             MpdProtocolError::InvalidMessage => write!(f, "invalid message"),
+            MpdProtocolError::ResponseTooLarge => write!(f, "response too large"),
         }
     }
 }
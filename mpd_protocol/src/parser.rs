@@ -12,10 +12,10 @@ use nom::{
     IResult,
 };
 
-use std::str::{self, from_utf8, FromStr};
+use std::str::{self, from_utf8, FromStr, Utf8Error};
 use std::sync::Arc;
 
-use crate::response::{Error, ResponseFieldCache};
+use crate::response::{Error, ErrorCode, ResponseFieldCache};
 
 #[derive(Debug, PartialEq, Eq)]
 pub(crate) enum ParsedComponent {
@@ -35,9 +35,14 @@ struct RawError<'raw> {
 }
 
 impl ParsedComponent {
+    /// Parse a single component.
+    ///
+    /// If `lossy` is set, field values which are not valid UTF-8 are decoded with replacement
+    /// characters instead of resulting in an error.
     pub(crate) fn parse<'i>(
         i: &'i [u8],
         field_cache: &'_ mut ResponseFieldCache,
+        lossy: bool,
     ) -> IResult<&'i [u8], ParsedComponent> {
         alt((
             map(tag("OK\n"), |_| ParsedComponent::EndOfResponse),
@@ -46,9 +51,17 @@ impl ParsedComponent {
             map(binary_field, |bin| ParsedComponent::BinaryField {
                 data_length: bin.len(),
             }),
-            map(key_value_field, |(k, v)| ParsedComponent::Field {
-                key: field_cache.insert(k),
-                value: String::from(v),
+            map_res(key_value_field, |(k, v)| {
+                let value = if lossy {
+                    String::from_utf8_lossy(v).into_owned()
+                } else {
+                    String::from(from_utf8(v)?)
+                };
+
+                Ok::<_, Utf8Error>(ParsedComponent::Field {
+                    key: field_cache.insert(k),
+                    value,
+                })
             }),
         ))(i)
     }
@@ -57,7 +70,7 @@ impl ParsedComponent {
 impl RawError<'_> {
     fn into_owned_error(self) -> Error {
         Error {
-            code: self.code,
+            code: ErrorCode::from(self.code),
             command_index: self.command_index,
             current_command: self.current_command.map(Box::from),
             message: Box::from(self.message),
@@ -124,14 +137,16 @@ fn error_current_command(i: &[u8]) -> IResult<&[u8], Option<&str>> {
 }
 
 /// Recognize a single key-value pair
-fn key_value_field(i: &[u8]) -> IResult<&[u8], (&str, &str)> {
+///
+/// The value is returned as raw bytes, since it may not be valid UTF-8.
+fn key_value_field(i: &[u8]) -> IResult<&[u8], (&str, &[u8])> {
     separated_pair(
         map_res(
             take_while1(|b| is_alphabetic(b) || b == b'_' || b == b'-'),
             from_utf8,
         ),
         tag(": "),
-        map_res(terminated(take_while(|b| b != b'\n'), newline), from_utf8),
+        terminated(take_while(|b| b != b'\n'), newline),
     )(i)
 }
 
@@ -147,6 +162,54 @@ fn binary_field(i: &[u8]) -> IResult<&[u8], &[u8]> {
     cut(terminated(take(length), newline))(i)
 }
 
+/// Find the end of the next response terminator (an `OK` or `ACK` line) in `i`.
+///
+/// Binary payloads are skipped without looking at their contents, since they may contain
+/// arbitrary data, including lines looking like terminators. `binary_pending` is the number of
+/// bytes of a binary payload (including its trailing newline) which remain to be skipped at the
+/// start of `i`, and is updated if a payload extends past the end of `i`.
+///
+/// Returns the number of bytes which may be discarded and whether a terminator was found. If no
+/// terminator was found, only the complete lines and the available payload data are included.
+pub(crate) fn skip_to_terminator(i: &[u8], binary_pending: &mut usize) -> (usize, bool) {
+    let mut pos = (*binary_pending).min(i.len());
+    *binary_pending -= pos;
+
+    if *binary_pending != 0 {
+        return (pos, false);
+    }
+
+    while let Some(end) = memchr::memchr(b'\n', &i[pos..]) {
+        let line = &i[pos..=pos + end];
+        pos += end + 1;
+
+        if line == b"OK\n" || line.starts_with(b"ACK ") {
+            return (pos, true);
+        }
+
+        if let Ok((_, length)) = binary_prefix(line) {
+            let payload = match length.checked_add(1) {
+                Some(payload) => payload,
+                None => {
+                    // No payload can be this long, treat the rest of the stream as part of it
+                    *binary_pending = usize::MAX;
+                    return (i.len(), false);
+                }
+            };
+            let available = i.len() - pos;
+
+            if payload > available {
+                *binary_pending = payload - available;
+                return (i.len(), false);
+            }
+
+            pos += payload;
+        }
+    }
+
+    (pos, false)
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -167,22 +230,22 @@ mod test {
         let keys = &mut ResponseFieldCache::new();
 
         assert_eq!(
-            ParsedComponent::parse(b"OK\n", keys),
+            ParsedComponent::parse(b"OK\n", keys, false),
             Ok((EMPTY, ParsedComponent::EndOfResponse))
         );
 
         assert_eq!(
-            ParsedComponent::parse(b"OK", keys),
+            ParsedComponent::parse(b"OK", keys, false),
             Err(NomErr::Incomplete(Needed::new(1)))
         );
 
         assert_eq!(
-            ParsedComponent::parse(b"list_OK\n", keys),
+            ParsedComponent::parse(b"list_OK\n", keys, false),
             Ok((EMPTY, ParsedComponent::EndOfFrame))
         );
 
         assert_eq!(
-            ParsedComponent::parse(b"list_OK", keys),
+            ParsedComponent::parse(b"list_OK", keys, false),
             Err(NomErr::Incomplete(Needed::new(1)))
         );
     }
@@ -194,11 +257,11 @@ mod test {
         let with_command = b"ACK [2@0] {random} Boolean (0/1) expected: foo\n";
 
         assert_eq!(
-            ParsedComponent::parse(no_command, keys),
+            ParsedComponent::parse(no_command, keys, false),
             Ok((
                 EMPTY,
                 ParsedComponent::Error(Error {
-                    code: 5,
+                    code: ErrorCode::UnknownCommand,
                     command_index: 0,
                     current_command: None,
                     message: Box::from("unknown command \"foo\""),
@@ -207,11 +270,11 @@ mod test {
         );
 
         assert_eq!(
-            ParsedComponent::parse(with_command, keys),
+            ParsedComponent::parse(with_command, keys, false),
             Ok((
                 EMPTY,
                 ParsedComponent::Error(Error {
-                    code: 2,
+                    code: ErrorCode::Argument,
                     command_index: 0,
                     current_command: Some(Box::from("random")),
                     message: Box::from("Boolean (0/1) expected: foo"),
@@ -225,7 +288,7 @@ mod test {
         let keys = &mut ResponseFieldCache::new();
 
         assert_eq!(
-            ParsedComponent::parse(b"foo: OK\n", keys),
+            ParsedComponent::parse(b"foo: OK\n", keys, false),
             Ok((
                 EMPTY,
                 ParsedComponent::Field {
@@ -236,7 +299,7 @@ mod test {
         );
 
         assert_eq!(
-            ParsedComponent::parse(b"foo_bar: hello world list_OK\n", keys),
+            ParsedComponent::parse(b"foo_bar: hello world list_OK\n", keys, false),
             Ok((
                 EMPTY,
                 ParsedComponent::Field {
@@ -246,28 +309,98 @@ mod test {
             ))
         );
 
-        assert!(ParsedComponent::parse(b"asdf: fooo", keys)
+        assert!(ParsedComponent::parse(b"asdf: fooo", keys, false)
             .unwrap_err()
             .is_incomplete());
     }
 
+    #[test]
+    fn invalid_utf8_field() {
+        let keys = &mut ResponseFieldCache::new();
+
+        assert!(ParsedComponent::parse(b"foo: b\xffr\n", keys, false).is_err());
+
+        assert_eq!(
+            ParsedComponent::parse(b"foo: b\xffr\n", keys, true),
+            Ok((
+                EMPTY,
+                ParsedComponent::Field {
+                    key: Arc::from("foo"),
+                    value: String::from("b\u{FFFD}r"),
+                }
+            ))
+        );
+    }
+
     #[test]
     fn binary_field() {
         let keys = &mut ResponseFieldCache::new();
 
         assert_eq!(
-            ParsedComponent::parse(b"binary: 6\nFOOBAR\n", keys),
+            ParsedComponent::parse(b"binary: 6\nFOOBAR\n", keys, false),
             Ok((EMPTY, ParsedComponent::BinaryField { data_length: 6 }))
         );
 
         assert_eq!(
-            ParsedComponent::parse(b"binary: 6\nF", keys),
+            ParsedComponent::parse(b"binary: 6\nF", keys, false),
             Err(NomErr::Incomplete(Needed::new(5)))
         );
 
         assert_eq!(
-            ParsedComponent::parse(b"binary: 12\n", keys),
+            ParsedComponent::parse(b"binary: 12\n", keys, false),
             Err(NomErr::Incomplete(Needed::new(12)))
         );
     }
+
+    #[test]
+    fn skip_to_terminator() {
+        let mut pending = 0;
+
+        assert_eq!(
+            super::skip_to_terminator(b"foo\nbar: baz\nOK\nnext", &mut pending),
+            (16, true)
+        );
+        assert_eq!(
+            super::skip_to_terminator(b"foo\nACK [5@0] {} x\n", &mut pending),
+            (19, true)
+        );
+        assert_eq!(
+            super::skip_to_terminator(b"foo\nlist_OK\nO", &mut pending),
+            (12, false)
+        );
+        assert_eq!(super::skip_to_terminator(b"", &mut pending), (0, false));
+        assert_eq!(pending, 0);
+    }
+
+    #[test]
+    fn skip_to_terminator_binary() {
+        let mut pending = 0;
+
+        assert_eq!(
+            super::skip_to_terminator(b"binary: 3\nOK\n\nOK\n", &mut pending),
+            (17, true)
+        );
+
+        assert_eq!(
+            super::skip_to_terminator(b"binary: 8\nfoo\nOK\n", &mut pending),
+            (17, false)
+        );
+        assert_eq!(pending, 2);
+
+        assert_eq!(
+            super::skip_to_terminator(b"x\nOK\n", &mut pending),
+            (5, true)
+        );
+        assert_eq!(pending, 0);
+
+        let overflowing = format!("binary: {}\nOK\n", usize::MAX);
+        assert_eq!(
+            super::skip_to_terminator(overflowing.as_bytes(), &mut pending),
+            (overflowing.len(), false)
+        );
+        assert_eq!(pending, usize::MAX);
+
+        assert_eq!(super::skip_to_terminator(b"OK\n", &mut pending), (3, false));
+        assert_eq!(pending, usize::MAX - 3);
+    }
 }
@@ -4,7 +4,8 @@ pub mod frame;
 
 use bytes::{Buf, BytesMut};
 use hashbrown::HashSet;
-use tracing::trace;
+use nom::{Err as NomErr, Needed};
+use tracing::{error, trace};
 
 use std::fmt;
 use std::iter::FusedIterator;
@@ -123,6 +124,16 @@ impl ResponseFieldCache {
 pub(crate) struct ResponseBuilder<'a> {
     field_cache: &'a mut ResponseFieldCache,
     state: ResponseState,
+    /// Minimum length the buffer needs to reach before it is worth attempting to parse again.
+    required_len: usize,
+    /// Length of the buffer up to which no terminating newline was found in the previous attempt.
+    scanned_len: usize,
+    /// Amount of data consumed for the response currently in progress.
+    consumed_len: usize,
+    /// Maximum amount of data for a single response.
+    max_len: Option<usize>,
+    /// Whether to replace invalid UTF-8 in field values instead of failing.
+    lossy: bool,
 }
 
 #[derive(Clone, Debug, PartialEq, Eq)]
@@ -142,6 +153,58 @@ impl<'a> ResponseBuilder<'a> {
         Self {
             field_cache,
             state: ResponseState::Initial,
+            required_len: 0,
+            scanned_len: 0,
+            consumed_len: 0,
+            max_len: None,
+            lossy: false,
+        }
+    }
+
+    /// Parse the next component from the start of `src`, returning it with its length in bytes.
+    ///
+    /// Returns `Ok(None)` if the buffer does not contain a complete component yet. In this case,
+    /// the amount of missing data is tracked so that repeated calls with a growing buffer don't
+    /// re-examine the same incomplete data again until it can possibly be complete.
+    fn next_component(
+        &mut self,
+        src: &[u8],
+    ) -> Result<Option<(ParsedComponent, usize)>, MpdProtocolError> {
+        if src.len() < self.required_len {
+            self.check_len(self.required_len)?;
+            return Ok(None);
+        }
+
+        // Every component ends with a newline, and since the previous attempt found the component
+        // to be incomplete, the terminating newline must come after the previously examined data.
+        let unscanned = &src[self.scanned_len.min(src.len())..];
+        if memchr::memchr(b'\n', unscanned).is_none() {
+            self.scanned_len = src.len();
+            self.check_len(src.len())?;
+            return Ok(None);
+        }
+
+        match ParsedComponent::parse(src, self.field_cache, self.lossy) {
+            Err(NomErr::Incomplete(needed)) => {
+                let needed = match needed {
+                    Needed::Size(n) => n.get(),
+                    Needed::Unknown => 1,
+                };
+
+                self.required_len = src.len() + needed;
+                self.scanned_len = src.len();
+                self.check_len(self.required_len)?;
+                Ok(None)
+            }
+            Err(_) => Err(MpdProtocolError::InvalidMessage),
+            Ok((remaining, component)) => {
+                let len = src.len() - remaining.len();
+                self.required_len = 0;
+                self.scanned_len = 0;
+                self.consumed_len += len;
+                self.check_len(0)?;
+                Ok(Some((component, len)))
+            }
         }
     }
 
@@ -149,14 +212,7 @@ impl<'a> ResponseBuilder<'a> {
         &mut self,
         src: &mut BytesMut,
     ) -> Result<Option<Response>, MpdProtocolError> {
-        while !src.is_empty() {
-            let (remaining, component) = match ParsedComponent::parse(src, self.field_cache) {
-                Err(e) if e.is_incomplete() => break,
-                Err(_) => return Err(MpdProtocolError::InvalidMessage),
-                Ok(p) => p,
-            };
-
-            let msg_end = src.len() - remaining.len();
+        while let Some((component, msg_end)) = self.next_component(src)? {
             let mut msg = src.split_to(msg_end);
 
             match component {
@@ -175,6 +231,73 @@ impl<'a> ResponseBuilder<'a> {
         Ok(None)
     }
 
+    /// Parse as much of a response as possible from `src`, without waiting for the response to
+    /// be completed.
+    ///
+    /// Fields are accumulated into a frame until either the available data is exhausted or an end
+    /// marker is encountered. In the latter case, the end marker is only consumed on the next call
+    /// so that it is returned separately.
+    pub(crate) fn parse_chunk(
+        &mut self,
+        src: &mut BytesMut,
+    ) -> Result<Option<ResponseChunk>, MpdProtocolError> {
+        let mut fields: Option<Frame> = None;
+
+        while let Some((component, msg_end)) = self.next_component(src)? {
+            let end = match component {
+                ParsedComponent::Field { key, value } => {
+                    trace!(?key, ?value, "parsed field");
+                    src.advance(msg_end);
+                    let frame = fields.get_or_insert_with(Frame::empty);
+                    frame.fields.push_field(key, value);
+                    continue;
+                }
+                ParsedComponent::BinaryField { data_length } => {
+                    let mut msg = src.split_to(msg_end);
+                    msg.advance(msg.len() - (data_length + 1));
+                    msg.truncate(data_length);
+                    trace!(length = msg.len(), "parsed binary field");
+                    fields.get_or_insert_with(Frame::empty).binary = Some(msg);
+                    continue;
+                }
+                ParsedComponent::Error(e) => ResponseChunk::Error(e),
+                ParsedComponent::EndOfFrame => ResponseChunk::EndOfFrame,
+                ParsedComponent::EndOfResponse => ResponseChunk::EndOfResponse,
+            };
+
+            if fields.is_some() {
+                // Return the fields parsed so far first, the end marker will be parsed again
+                break;
+            }
+
+            src.advance(msg_end);
+            return Ok(Some(end));
+        }
+
+        Ok(fields.map(ResponseChunk::Fields))
+    }
+
+    /// Limit the amount of data a single response may consist of.
+    pub(crate) fn set_max_len(&mut self, max_len: Option<usize>) {
+        self.max_len = max_len;
+    }
+
+    /// Decode field values which are not valid UTF-8 with replacement characters.
+    pub(crate) fn set_lossy(&mut self, lossy: bool) {
+        self.lossy = lossy;
+    }
+
+    /// Returns an error if the current response and any pending data exceed the maximum length.
+    fn check_len(&self, pending: usize) -> Result<(), MpdProtocolError> {
+        match self.max_len {
+            Some(max_len) if self.consumed_len + pending > max_len => {
+                error!(max_len, "response exceeds maximum length");
+                Err(MpdProtocolError::ResponseTooLarge)
+            }
+            _ => Ok(()),
+        }
+    }
+
     pub(crate) fn is_frame_in_progress(&self) -> bool {
         self.state != ResponseState::Initial
     }
@@ -231,6 +354,7 @@ impl<'a> ResponseBuilder<'a> {
 
     fn finish(&mut self) -> Response {
         trace!("finished response");
+        self.consumed_len = 0;
         match mem::replace(&mut self.state, ResponseState::Initial) {
             ResponseState::Initial => Response::empty(),
             ResponseState::InProgress { current } => Response {
@@ -248,6 +372,7 @@ impl<'a> ResponseBuilder<'a> {
 
     fn error(&mut self, error: Error) -> Response {
         trace!(?error, "parsed error");
+        self.consumed_len = 0;
         match mem::replace(&mut self.state, ResponseState::Initial) {
             ResponseState::Initial | ResponseState::InProgress { .. } => Response {
                 frames: Vec::new(),
@@ -263,6 +388,31 @@ impl<'a> ResponseBuilder<'a> {
     }
 }
 
+/// Part of a response, as returned by [`Connection::receive_partial`].
+///
+/// [`Connection::receive_partial`]: crate::Connection::receive_partial
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ResponseChunk {
+    /// One or more fields (and possibly a binary blob) belonging to the current frame.
+    ///
+    /// A frame may be split across any number of these chunks, and there is no guarantee that
+    /// the split occurs at a particular field.
+    Fields(Frame),
+    /// The end of a frame in a command list (`list_OK`).
+    EndOfFrame,
+    /// The successful end of the response (`OK`).
+    EndOfResponse,
+    /// An error, which also ends the response.
+    Error(Error),
+}
+
+impl ResponseChunk {
+    /// Returns `true` if this chunk ends the response (either successfully or with an error).
+    pub fn is_end_of_response(&self) -> bool {
+        matches!(self, ResponseChunk::EndOfResponse | ResponseChunk::Error(_))
+    }
+}
+
 /// Iterator over frames in a response, as returned by [`Response::frames`].
 #[derive(Clone, Debug)]
 pub struct FramesRef<'a> {
@@ -366,10 +516,8 @@ impl IntoIterator for Response {
 /// A response to a command indicating an error.
 #[derive(Clone, Debug, Default, PartialEq, Eq)]
 pub struct Error {
-    /// Error code. See [the MPD source][mpd-error-def] for a list of of possible values.
-    ///
-    /// [mpd-error-def]: https://github.com/MusicPlayerDaemon/MPD/blob/master/src/protocol/Ack.hxx#L30
-    pub code: u64,
+    /// Error code.
+    pub code: ErrorCode,
     /// Index of command in a command list that caused this error. 0 when not in a command list.
     pub command_index: u64,
     /// Command that returned the error, if applicable.
@@ -378,6 +526,86 @@ pub struct Error {
     pub message: Box<str>,
 }
 
+/// Error codes returned by MPD. See [the MPD source][mpd-error-def] for their definitions.
+///
+/// [mpd-error-def]: https://github.com/MusicPlayerDaemon/MPD/blob/master/src/protocol/Ack.hxx#L30
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum ErrorCode {
+    /// The command is only valid inside a command list (`ACK_ERROR_NOT_LIST`).
+    NotList,
+    /// An argument was invalid (`ACK_ERROR_ARG`).
+    Argument,
+    /// The given password was incorrect (`ACK_ERROR_PASSWORD`).
+    Password,
+    /// The client is not permitted to execute the command (`ACK_ERROR_PERMISSION`).
+    Permission,
+    /// The command is unknown, or it failed for an unspecified reason (`ACK_ERROR_UNKNOWN`).
+    UnknownCommand,
+    /// The requested entity does not exist (`ACK_ERROR_NO_EXIST`).
+    NoExist,
+    /// The maximum playlist length was reached (`ACK_ERROR_PLAYLIST_MAX`).
+    PlaylistMax,
+    /// A system error occurred on the server (`ACK_ERROR_SYSTEM`).
+    System,
+    /// A playlist could not be loaded (`ACK_ERROR_PLAYLIST_LOAD`).
+    PlaylistLoad,
+    /// A database update is already in progress (`ACK_ERROR_UPDATE_ALREADY`).
+    UpdateAlready,
+    /// The player is not in the required state (`ACK_ERROR_PLAYER_SYNC`).
+    PlayerSync,
+    /// The entity to be created already exists (`ACK_ERROR_EXIST`).
+    Exist,
+    /// An error code not known to this library.
+    Unknown(u64),
+}
+
+impl From<u64> for ErrorCode {
+    fn from(code: u64) -> Self {
+        match code {
+            1 => ErrorCode::NotList,
+            2 => ErrorCode::Argument,
+            3 => ErrorCode::Password,
+            4 => ErrorCode::Permission,
+            5 => ErrorCode::UnknownCommand,
+            50 => ErrorCode::NoExist,
+            51 => ErrorCode::PlaylistMax,
+            52 => ErrorCode::System,
+            53 => ErrorCode::PlaylistLoad,
+            54 => ErrorCode::UpdateAlready,
+            55 => ErrorCode::PlayerSync,
+            56 => ErrorCode::Exist,
+            other => ErrorCode::Unknown(other),
+        }
+    }
+}
+
+impl From<ErrorCode> for u64 {
+    fn from(code: ErrorCode) -> Self {
+        match code {
+            ErrorCode::NotList => 1,
+            ErrorCode::Argument => 2,
+            ErrorCode::Password => 3,
+            ErrorCode::Permission => 4,
+            ErrorCode::UnknownCommand => 5,
+            ErrorCode::NoExist => 50,
+            ErrorCode::PlaylistMax => 51,
+            ErrorCode::System => 52,
+            ErrorCode::PlaylistLoad => 53,
+            ErrorCode::UpdateAlready => 54,
+            ErrorCode::PlayerSync => 55,
+            ErrorCode::Exist => 56,
+            ErrorCode::Unknown(other) => other,
+        }
+    }
+}
+
+impl Default for ErrorCode {
+    fn default() -> Self {
+        ErrorCode::Unknown(0)
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -558,7 +786,7 @@ mod test {
             Some(Response {
                 frames: vec![],
                 error: Some(Error {
-                    code: 5,
+                    code: ErrorCode::UnknownCommand,
                     command_index: 0,
                     current_command: None,
                     message: Box::from("unknown command \"foo\""),
@@ -593,6 +821,30 @@ mod test {
         assert_eq!(io, "");
     }
 
+    #[test]
+    fn incremental_input() {
+        let input: &[u8] = b"foo: bar\nbinary: 6\nbinary\nhello: world\nOK\n";
+        let mut io = BytesMut::new();
+        let mut field_cache = ResponseFieldCache::new();
+        let mut builder = ResponseBuilder::new(&mut field_cache);
+
+        for &byte in &input[..input.len() - 1] {
+            io.extend_from_slice(&[byte]);
+            assert_eq!(builder.parse(&mut io).unwrap(), None);
+        }
+
+        io.extend_from_slice(b"\n");
+
+        assert_eq!(
+            builder.parse(&mut io).unwrap(),
+            Some(Response {
+                frames: vec![frame([("foo", "bar"), ("hello", "world")], Some(b"binary"))],
+                error: None
+            })
+        );
+        assert_eq!(io, "");
+    }
+
     #[test]
     fn command_list() {
         let mut io = BytesMut::from("foo: bar\n");
@@ -663,7 +915,7 @@ mod test {
             Some(Response {
                 frames: vec![Frame::empty()],
                 error: Some(Error {
-                    code: 5,
+                    code: ErrorCode::UnknownCommand,
                     command_index: 1,
                     current_command: None,
                     message: Box::from("unknown command \"foo\""),
@@ -673,6 +925,87 @@ mod test {
         assert_eq!(builder.state, ResponseState::Initial);
     }
 
+    #[test]
+    fn chunks() {
+        let mut io = BytesMut::from("foo: bar\nhello: world\nlist_");
+        let mut field_cache = ResponseFieldCache::new();
+        let mut builder = ResponseBuilder::new(&mut field_cache);
+
+        assert_eq!(
+            builder.parse_chunk(&mut io).unwrap(),
+            Some(ResponseChunk::Fields(frame(
+                [("foo", "bar"), ("hello", "world")],
+                None
+            )))
+        );
+        assert_eq!(io, "list_");
+
+        assert_eq!(builder.parse_chunk(&mut io).unwrap(), None);
+
+        io.extend_from_slice(b"OK\nbinary: 3\nOK\n\nACK [5@1] {} unknown command \"foo\"\n");
+
+        assert_eq!(
+            builder.parse_chunk(&mut io).unwrap(),
+            Some(ResponseChunk::EndOfFrame)
+        );
+        assert_eq!(
+            builder.parse_chunk(&mut io).unwrap(),
+            Some(ResponseChunk::Fields(frame([], Some(b"OK\n"))))
+        );
+        assert_eq!(
+            builder.parse_chunk(&mut io).unwrap(),
+            Some(ResponseChunk::Error(Error {
+                code: ErrorCode::UnknownCommand,
+                command_index: 1,
+                current_command: None,
+                message: Box::from("unknown command \"foo\""),
+            }))
+        );
+        assert_eq!(io, "");
+
+        io.extend_from_slice(b"OK\n");
+        assert_eq!(
+            builder.parse_chunk(&mut io).unwrap(),
+            Some(ResponseChunk::EndOfResponse)
+        );
+    }
+
+    #[test]
+    fn error_code_conversion() {
+        for code in 0..100 {
+            assert_eq!(u64::from(ErrorCode::from(code)), code);
+        }
+
+        assert_eq!(ErrorCode::from(50), ErrorCode::NoExist);
+        assert_eq!(ErrorCode::from(42), ErrorCode::Unknown(42));
+    }
+
+    #[test]
+    fn max_len() {
+        let mut field_cache = ResponseFieldCache::new();
+        let mut builder = ResponseBuilder::new(&mut field_cache);
+        builder.set_max_len(Some(16));
+
+        let mut io = BytesMut::from("foo: bar\nOK\n");
+        assert_matches!(builder.parse(&mut io), Ok(Some(_)));
+
+        let mut io = BytesMut::from("foo: bar\nhello: world\nOK\n");
+        assert_matches!(
+            builder.parse(&mut io),
+            Err(MpdProtocolError::ResponseTooLarge)
+        );
+
+        let mut field_cache = ResponseFieldCache::new();
+        let mut builder = ResponseBuilder::new(&mut field_cache);
+        builder.set_max_len(Some(16));
+
+        let mut io = BytesMut::from("binary: 100\n");
+        assert_matches!(
+            builder.parse(&mut io),
+            Err(MpdProtocolError::ResponseTooLarge)
+        );
+    }
+
     #[test]
     fn key_interning() {
         let mut io = BytesMut::from("foo: bar\nfoo: baz\nOK\n");